@@ -0,0 +1,95 @@
+use crate::tree::{FileSystem, NodeId};
+
+pub struct Disk {
+    pub capacity: usize,
+    pub required_free: usize,
+}
+
+impl Default for Disk {
+    fn default() -> Self {
+        Self {
+            capacity: 70000000,
+            required_free: 30000000,
+        }
+    }
+}
+
+impl Disk {
+    /// How much still has to be deleted to reach the required free space.
+    pub fn space_to_free(&self, file_system: &FileSystem) -> usize {
+        let free_space = self
+            .capacity
+            .saturating_sub(file_system.size(FileSystem::ROOT));
+        self.required_free.saturating_sub(free_space)
+    }
+}
+
+/// Directories of at most `threshold` in size.
+pub fn directories_under(file_system: &FileSystem, threshold: usize) -> Vec<NodeId> {
+    file_system
+        .directories()
+        .filter(|&dir| file_system.size(dir) <= threshold)
+        .collect()
+}
+
+/// Directories of at least `threshold` in size.
+pub fn directories_over(file_system: &FileSystem, threshold: usize) -> Vec<NodeId> {
+    file_system
+        .directories()
+        .filter(|&dir| file_system.size(dir) >= threshold)
+        .collect()
+}
+
+pub fn smallest_freeing(file_system: &FileSystem, amount: usize) -> Option<NodeId> {
+    directories_over(file_system, amount)
+        .into_iter()
+        .min_by_key(|&dir| file_system.size(dir))
+}
+
+pub fn largest(file_system: &FileSystem, k: usize) -> Vec<NodeId> {
+    let mut directories = file_system.directories().collect::<Vec<_>>();
+    directories.sort_by_key(|&dir| std::cmp::Reverse(file_system.size(dir)));
+    directories.truncate(k);
+    directories
+}
+
+/// Picks directories to delete until at least `amount` is freed: the smallest one that
+/// covers what is left if there is one, otherwise the largest one, skipping directories
+/// nested in or containing an already picked one. The root is never picked.
+pub fn deletion_plan(file_system: &FileSystem, amount: usize) -> Option<Vec<NodeId>> {
+    let mut plan: Vec<NodeId> = vec![];
+    let mut freed = 0;
+    while freed < amount {
+        let candidates = file_system.directories().filter(|&dir| {
+            dir != FileSystem::ROOT
+                && plan.iter().all(|&picked| {
+                    !is_ancestor(file_system, picked, dir) && !is_ancestor(file_system, dir, picked)
+                })
+        });
+        let (covering, other): (Vec<_>, Vec<_>) =
+            candidates.partition(|&dir| freed + file_system.size(dir) >= amount);
+        let next = match covering
+            .into_iter()
+            .min_by_key(|&dir| file_system.size(dir))
+        {
+            Some(dir) => dir,
+            None => other.into_iter().max_by_key(|&dir| file_system.size(dir))?,
+        };
+        freed += file_system.size(next);
+        plan.push(next);
+    }
+
+    Some(plan)
+}
+
+/// Whether `ancestor` is `node` or contains it.
+fn is_ancestor(file_system: &FileSystem, ancestor: NodeId, node: NodeId) -> bool {
+    let mut current = Some(node);
+    while let Some(id) = current {
+        if id == ancestor {
+            return true;
+        }
+        current = file_system.node(id).parent;
+    }
+    false
+}
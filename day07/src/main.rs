@@ -1,80 +1,140 @@
-use std::collections::HashMap;
+mod cleanup;
+mod replay;
+mod tree;
 
-type FileSystem = HashMap<String, Vec<Element>>;
+use cleanup::Disk;
+use std::{env, fs, io, path::Path};
+use tree::FileSystem;
 
-enum Element {
-    File(usize, String),
-    Directory(String),
-}
+const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+const EXAMPLE_TREE: &str = "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)
+";
 
 fn part_1(file_system: &FileSystem) -> usize {
-    file_system
-        .keys()
-        .map(|dir_name| calculate_dir_size(file_system, dir_name))
-        .filter(|size| size < &100000)
+    cleanup::directories_under(file_system, 100000)
+        .into_iter()
+        .map(|dir| file_system.size(dir))
         .sum()
 }
 
 fn part_2(file_system: &FileSystem) -> usize {
-    let free_space = 70000000 - calculate_dir_size(file_system, "/");
-    let space_needed = 30000000 - free_space;
-    file_system
-        .keys()
-        .map(|dir_name| calculate_dir_size(file_system, dir_name))
-        .filter(|size| size >= &space_needed)
-        .min()
-        .unwrap()
+    let space_needed = Disk::default().space_to_free(file_system);
+    file_system.size(cleanup::smallest_freeing(file_system, space_needed).unwrap())
 }
 
 fn main() {
-    let input = std::fs::read_to_string("input").unwrap();
+    let example = FileSystem::parse(EXAMPLE).unwrap();
+    assert_eq!(part_1(&example), 95437);
+    assert_eq!(part_2(&example), 24933642);
+    assert_eq!(example.tree(), EXAMPLE_TREE);
+    assert_eq!(example.du(), "584\t/a/e\n93K\t/a\n24M\t/d\n47M\t/\n");
 
-    let mut file_system = FileSystem::new();
-    let mut current_path: Vec<String> = vec![];
+    let paths = |dirs: Vec<usize>| {
+        dirs.into_iter()
+            .map(|dir| example.path(dir))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(paths(cleanup::largest(&example, 2)), ["/", "/d"]);
+    assert_eq!(
+        paths(cleanup::directories_over(&example, 1000000)),
+        ["/", "/d"]
+    );
+    let small_disk = Disk {
+        capacity: 50000000,
+        required_free: 26618835,
+    };
+    let to_free = small_disk.space_to_free(&example);
+    assert_eq!(to_free, 25000000);
+    assert_eq!(
+        cleanup::smallest_freeing(&example, to_free),
+        Some(FileSystem::ROOT)
+    );
+    assert_eq!(
+        paths(cleanup::deletion_plan(&example, to_free).unwrap()),
+        ["/d", "/a"]
+    );
+    assert_eq!(cleanup::deletion_plan(&example, 30000000), None);
 
-    for line in input.lines() {
-        if line.starts_with('$') {
-            if line.contains("cd") {
-                let directory_name = line.split_once("$ cd ").unwrap().1;
-                if directory_name == ".." {
-                    current_path.pop();
-                } else {
-                    current_path.push(directory_name.into());
-                    let path = current_path.join("/");
-                    file_system.entry(path).or_default();
-                }
-            }
-        } else if line.starts_with("dir") {
-            let (_, dir) = line.split_once(' ').unwrap();
-            let path = current_path.join("/");
-            file_system
-                .get_mut(&path)
-                .unwrap()
-                .push(Element::Directory(dir.into()));
-        } else {
-            let (size, file_name) = line.split_once(' ').unwrap();
-            let path = current_path.join("/");
-            file_system
-                .get_mut(&path)
-                .unwrap()
-                .push(Element::File(size.parse().unwrap(), file_name.into()));
-        }
+    let relisted = FileSystem::parse(
+        &(EXAMPLE.to_string() + "\n$ cd /\n$ ls\n14848514 b.txt\n$ cd a\n$ ls\n29116 f"),
+    )
+    .unwrap();
+    assert_eq!(relisted.tree(), EXAMPLE_TREE);
+    assert_eq!(part_1(&relisted), 95437);
+
+    let materialized = replay::materialize_in_temp(&example, "example").unwrap();
+    let transcript = replay::transcript_from_dir(&materialized).unwrap();
+    fs::remove_dir_all(&materialized).unwrap();
+    let replayed = FileSystem::parse(&transcript).unwrap();
+    assert_eq!(
+        replayed.size(FileSystem::ROOT),
+        example.size(FileSystem::ROOT)
+    );
+    assert_eq!(part_1(&replayed), 95437);
+    assert_eq!(part_2(&replayed), 24933642);
+
+    for unsafe_transcript in [
+        "$ cd /\n$ ls\n123 /tmp/day07-escape",
+        "$ cd /\n$ ls\ndir ..",
+        "$ cd /\n$ cd a\n$ ls\n1 b/../../c",
+        "$ cd /\n$ ls\n1 .",
+        "$ cd /\n$ ls\n1 ",
+    ] {
+        let unsafe_tree = FileSystem::parse(unsafe_transcript).unwrap();
+        let error = replay::materialize_in_temp(&unsafe_tree, "unsafe").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
     }
+    assert!(!Path::new("/tmp/day07-escape").exists());
+
+    let input = fs::read_to_string("input").unwrap();
+    let file_system = FileSystem::parse(&input).unwrap();
 
     assert_eq!(part_1(&file_system), 1084134);
     assert_eq!(part_2(&file_system), 6183184);
-}
 
-fn calculate_dir_size(file_system: &FileSystem, dir_name: &str) -> usize {
-    file_system
-        .get(dir_name)
-        .unwrap()
-        .iter()
-        .map(|el| match el {
-            Element::File(size, _) => *size,
-            Element::Directory(name) => {
-                calculate_dir_size(file_system, &(dir_name.to_string() + "/" + name))
-            }
-        })
-        .sum()
+    match (env::args().nth(1).as_deref(), env::args().nth(2)) {
+        (Some("transcript"), Some(dir)) => {
+            print!("{}", replay::transcript_from_dir(Path::new(&dir)).unwrap())
+        }
+        (Some("materialize"), Some(dir)) => {
+            replay::materialize(&file_system, Path::new(&dir)).unwrap()
+        }
+        _ => (),
+    }
 }
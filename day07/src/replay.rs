@@ -0,0 +1,95 @@
+use crate::tree::{FileSystem, NodeId, NodeKind};
+use std::{
+    fs::{self, File},
+    io,
+    path::{self, Component, Path, PathBuf},
+};
+
+/// Walks a real directory and writes the `cd`/`ls` session that would explore it.
+/// Entries are listed by name; symlinks and other special files are skipped.
+pub fn transcript_from_dir(root: &Path) -> io::Result<String> {
+    let mut transcript = String::from("$ cd /\n");
+    explore(root, &mut transcript)?;
+    Ok(transcript)
+}
+
+fn explore(dir: &Path, transcript: &mut String) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    *transcript += "$ ls\n";
+    let mut subdirectories = vec![];
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let metadata = entry.path().symlink_metadata()?;
+        if metadata.is_dir() {
+            *transcript += &format!("dir {name}\n");
+            subdirectories.push((name, entry.path()));
+        } else if metadata.is_file() {
+            *transcript += &format!("{} {name}\n", metadata.len());
+        }
+    }
+
+    for (name, path) in subdirectories {
+        *transcript += &format!("$ cd {name}\n");
+        explore(&path, transcript)?;
+        *transcript += "$ cd ..\n";
+    }
+
+    Ok(())
+}
+
+/// Recreates the tree under `root`, with every file a sparse file of its listed size.
+/// Nothing is written if any name could lead outside `root`.
+pub fn materialize(file_system: &FileSystem, root: &Path) -> io::Result<()> {
+    check_names(file_system, FileSystem::ROOT)?;
+    fs::create_dir_all(root)?;
+    create_children(file_system, FileSystem::ROOT, root)
+}
+
+/// Rejects names that are not a single plain path component, such as `..` or `/etc/passwd`.
+fn check_names(file_system: &FileSystem, dir: NodeId) -> io::Result<()> {
+    for &child in file_system.children(dir) {
+        let name = &file_system.node(child).name;
+        let plain = !name.contains(['/', path::MAIN_SEPARATOR])
+            && matches!(
+                Path::new(name).components().collect::<Vec<_>>().as_slice(),
+                [Component::Normal(component)] if *component == name.as_str()
+            );
+        if !plain {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsafe name {name:?} in {}", file_system.path(dir)),
+            ));
+        }
+        check_names(file_system, child)?;
+    }
+
+    Ok(())
+}
+
+fn create_children(file_system: &FileSystem, dir: NodeId, path: &Path) -> io::Result<()> {
+    for &child in file_system.children(dir) {
+        let node = file_system.node(child);
+        let child_path = path.join(&node.name);
+        match node.kind {
+            NodeKind::Directory(_) => {
+                fs::create_dir(&child_path)?;
+                create_children(file_system, child, &child_path)?;
+            }
+            NodeKind::File => File::create(&child_path)?.set_len(node.size as u64)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Materializes into a fresh directory under the system temp dir and returns its path.
+pub fn materialize_in_temp(file_system: &FileSystem, label: &str) -> io::Result<PathBuf> {
+    let root = std::env::temp_dir().join(format!("day07-{label}-{}", std::process::id()));
+    if root.exists() {
+        fs::remove_dir_all(&root)?;
+    }
+    materialize(file_system, &root)?;
+    Ok(root)
+}
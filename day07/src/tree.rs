@@ -0,0 +1,202 @@
+pub type NodeId = usize;
+
+pub enum NodeKind {
+    Directory(Vec<NodeId>),
+    File,
+}
+
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+    /// File size, or cumulative size of everything below a directory.
+    pub size: usize,
+}
+
+/// Directory tree stored in an arena, with directory sizes kept up to date on every insert.
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                name: "/".into(),
+                parent: None,
+                kind: NodeKind::Directory(vec![]),
+                size: 0,
+            }],
+        }
+    }
+
+    /// Replays a terminal session of `cd` and `ls` commands.
+    pub fn parse(transcript: &str) -> Result<Self, String> {
+        let mut file_system = Self::new();
+        let mut current = Self::ROOT;
+
+        for (i, line) in transcript.lines().enumerate() {
+            let error = |message: &str| format!("line {}: {message}: {line}", i + 1);
+            if let Some(command) = line.strip_prefix("$ ") {
+                match command.split_once(' ') {
+                    Some(("cd", "/")) => current = Self::ROOT,
+                    Some(("cd", "..")) => {
+                        current = file_system.nodes[current].parent.unwrap_or(Self::ROOT)
+                    }
+                    Some(("cd", name)) => current = file_system.add_directory(current, name),
+                    None if command == "ls" => (),
+                    _ => return Err(error("unknown command")),
+                }
+            } else if let Some(name) = line.strip_prefix("dir ") {
+                file_system.add_directory(current, name);
+            } else {
+                let (size, name) = line.split_once(' ').ok_or_else(|| error("bad entry"))?;
+                let size = size.parse().map_err(|_| error("bad file size"))?;
+                file_system.add_file(current, name, size);
+            }
+        }
+
+        Ok(file_system)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            NodeKind::Directory(children) => children,
+            NodeKind::File => &[],
+        }
+    }
+
+    pub fn child(&self, directory: NodeId, name: &str) -> Option<NodeId> {
+        self.children(directory)
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].name == name)
+    }
+
+    /// Returns the existing directory if it was already seen.
+    pub fn add_directory(&mut self, parent: NodeId, name: &str) -> NodeId {
+        if let Some(existing) = self.child(parent, name) {
+            return existing;
+        }
+        self.insert(parent, name, NodeKind::Directory(vec![]))
+    }
+
+    /// Listing the same file again does not count it twice.
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: usize) -> NodeId {
+        if let Some(existing) = self.child(parent, name) {
+            return existing;
+        }
+        let id = self.insert(parent, name, NodeKind::File);
+        self.grow(id, size);
+        id
+    }
+
+    fn insert(&mut self, parent: NodeId, name: &str, kind: NodeKind) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.into(),
+            parent: Some(parent),
+            kind,
+            size: 0,
+        });
+        if let NodeKind::Directory(children) = &mut self.nodes[parent].kind {
+            children.push(id);
+        }
+        id
+    }
+
+    fn grow(&mut self, mut id: NodeId, size: usize) {
+        loop {
+            self.nodes[id].size += size;
+            match self.nodes[id].parent {
+                Some(parent) => id = parent,
+                None => break,
+            }
+        }
+    }
+
+    pub fn size(&self, id: NodeId) -> usize {
+        self.nodes[id].size
+    }
+
+    pub fn is_directory(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Directory(_))
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|&id| self.is_directory(id))
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        match self.nodes[id].parent {
+            None => "/".into(),
+            Some(Self::ROOT) => format!("/{}", self.nodes[id].name),
+            Some(parent) => format!("{}/{}", self.path(parent), self.nodes[id].name),
+        }
+    }
+
+    /// Renders the tree the way the puzzle description does.
+    pub fn tree(&self) -> String {
+        let mut rendered = String::new();
+        self.render_tree(Self::ROOT, 0, &mut rendered);
+        rendered
+    }
+
+    fn render_tree(&self, id: NodeId, depth: usize, rendered: &mut String) {
+        let node = &self.nodes[id];
+        let indent = "  ".repeat(depth);
+        match node.kind {
+            NodeKind::Directory(_) => *rendered += &format!("{indent}- {} (dir)\n", node.name),
+            NodeKind::File => {
+                *rendered += &format!("{indent}- {} (file, size={})\n", node.name, node.size)
+            }
+        }
+        for &child in self.children(id) {
+            self.render_tree(child, depth + 1, rendered);
+        }
+    }
+
+    /// Lists every directory with its human readable size, children before parents.
+    pub fn du(&self) -> String {
+        let mut rendered = String::new();
+        self.render_du(Self::ROOT, &mut rendered);
+        rendered
+    }
+
+    fn render_du(&self, id: NodeId, rendered: &mut String) {
+        for &child in self.children(id) {
+            if self.is_directory(child) {
+                self.render_du(child, rendered);
+            }
+        }
+        *rendered += &format!("{}\t{}\n", human_size(self.size(id)), self.path(id));
+    }
+}
+
+/// Formats like `du -h`: powers of 1024, rounded up, one decimal below 10.
+fn human_size(size: usize) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64;
+    let mut unit = 0;
+    value /= 1024.0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        let rounded = (value * 10.0).ceil() / 10.0;
+        if rounded < 10.0 {
+            return format!("{rounded:.1}{}", UNITS[unit]);
+        }
+    }
+    format!("{}{}", value.ceil(), UNITS[unit])
+}
@@ -0,0 +1,48 @@
+use grid::{Grid, Position};
+use std::{fmt::Display, fs, io, path::Path};
+
+const PIXELS_PER_TREE: usize = 8;
+
+pub fn write_csv<T: Display>(grid: &Grid<T>, path: impl AsRef<Path>) -> io::Result<()> {
+    let csv = (0..grid.height())
+        .map(|y| {
+            grid.row(y)
+                .map(|cell| cell.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(path, csv + "\n")
+}
+
+/// Writes a binary PPM image with every cell drawn as a square of `color(cell)`.
+pub fn write_ppm<T>(
+    grid: &Grid<T>,
+    path: impl AsRef<Path>,
+    color: impl Fn(&T) -> [u8; 3],
+) -> io::Result<()> {
+    let (width, height) = (
+        grid.width() * PIXELS_PER_TREE,
+        grid.height() * PIXELS_PER_TREE,
+    );
+    let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+    for y in 0..height {
+        for x in 0..width {
+            let cell = &grid[Position::new(x / PIXELS_PER_TREE, y / PIXELS_PER_TREE)];
+            image.extend(color(cell));
+        }
+    }
+    fs::write(path, image)
+}
+
+/// Black through red and yellow to white for `value` in `0..=max`.
+pub fn heat(value: usize, max: usize) -> [u8; 3] {
+    let t = if max == 0 {
+        0.0
+    } else {
+        value as f64 / max as f64
+    };
+    let channel = |from: f64| ((t * 3.0 - from).clamp(0.0, 1.0) * 255.0) as u8;
+    [channel(0.0), channel(1.0), channel(2.0)]
+}
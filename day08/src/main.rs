@@ -1,26 +1,55 @@
-use std::{collections::HashSet, fs::read_to_string};
+mod export;
 
-fn part_1(trees: &[Vec<u32>]) -> usize {
+use grid::{Grid, Position};
+use std::{collections::HashSet, env, fs::read_to_string, io, iter::once, path::Path};
+
+const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390";
+
+fn part_1(trees: &Grid<u32>) -> usize {
+    visibility_mask(trees).find_all(|&visible| visible).count()
+}
+
+fn visibility_mask(trees: &Grid<u32>) -> Grid<bool> {
     let mut visible = HashSet::new();
-    let grid_size = trees.len();
 
     look_from_left(trees, &mut visible);
     look_from_right(trees, &mut visible);
     look_from_top(trees, &mut visible);
     look_from_bottom(trees, &mut visible);
 
-    visible.len() + 4 * grid_size - 4
+    let mut mask = Grid::filled(trees.width(), trees.height(), false);
+    for position in trees.positions() {
+        mask[position] = visible.contains(&position)
+            || position.x == 0
+            || position.y == 0
+            || position.x == trees.width() - 1
+            || position.y == trees.height() - 1;
+    }
+
+    mask
+}
+
+fn part_2(trees: &Grid<u32>) -> usize {
+    scenic_scores(trees)
+        .iter()
+        .map(|(_, score)| *score)
+        .max()
+        .unwrap()
 }
 
-fn part_2(trees: &[Vec<u32>]) -> usize {
-    let grid_size = trees.len();
+fn part_2_reference(trees: &Grid<u32>) -> usize {
     let mut max = 0;
-    for y in 1..grid_size - 1 {
-        for x in 1..grid_size - 1 {
-            let right = look_right(trees, x, y);
-            let left = look_left(trees, x, y);
-            let top = look_up(trees, y, x);
-            let bottom = look_down(trees, y, x);
+    for y in 1..trees.height() - 1 {
+        for x in 1..trees.width() - 1 {
+            let position = Position::new(x, y);
+            let right = look_right(trees, position);
+            let left = look_left(trees, position);
+            let top = look_up(trees, position);
+            let bottom = look_down(trees, position);
             let score = right * left * top * bottom;
             if score > max {
                 max = score;
@@ -31,105 +60,172 @@ fn part_2(trees: &[Vec<u32>]) -> usize {
     max
 }
 
-fn look_from_left(trees: &[Vec<u32>], visible: &mut HashSet<(usize, usize)>) {
-    let grid_size = trees.len();
-    for y in 1..grid_size - 1 {
-        let mut current_heighest = trees[y][0];
-        for x in 1..grid_size - 1 {
-            if trees[y][x] > current_heighest {
-                visible.insert((x, y));
-                current_heighest = trees[y][x]
+fn scenic_scores(trees: &Grid<u32>) -> Grid<usize> {
+    let distances = [(1, 0), (-1, 0), (0, -1), (0, 1)].map(|dir| viewing_distances(trees, dir));
+    let mut scores = Grid::filled(trees.width(), trees.height(), 1);
+    for position in trees.positions() {
+        for distance in &distances {
+            scores[position] *= distance[position];
+        }
+    }
+
+    scores
+}
+
+// Every line is scanned once from the edge the trees look towards, keeping a stack of
+// trees that are still candidates for blocking the view (heights non-increasing).
+fn viewing_distances(trees: &Grid<u32>, direction: (isize, isize)) -> Grid<usize> {
+    let (width, height) = (trees.width(), trees.height());
+    let line_starts = match direction {
+        (-1, 0) => (0..height).map(|y| Position::new(0, y)).collect::<Vec<_>>(),
+        (1, 0) => (0..height).map(|y| Position::new(width - 1, y)).collect(),
+        (0, -1) => (0..width).map(|x| Position::new(x, 0)).collect(),
+        (0, 1) => (0..width).map(|x| Position::new(x, height - 1)).collect(),
+        _ => panic!("unsupported direction {direction:?}"),
+    };
+    let scan_direction = (-direction.0, -direction.1);
+
+    let mut distances = Grid::filled(width, height, 0);
+    for start in line_starts {
+        let mut blocking: Vec<(usize, u32)> = vec![];
+        let line = once(start).chain(trees.ray(start, scan_direction));
+        for (i, position) in line.enumerate() {
+            let tree = trees[position];
+            while blocking.last().is_some_and(|&(_, height)| height < tree) {
+                blocking.pop();
             }
+            distances[position] = blocking.last().map_or(i, |&(j, _)| i - j);
+            blocking.push((i, tree));
         }
     }
+
+    distances
 }
 
-fn look_from_right(trees: &[Vec<u32>], visible: &mut HashSet<(usize, usize)>) {
-    let grid_size = trees.len();
-    for y in 1..grid_size - 1 {
-        let mut current_heighest = trees[y][grid_size - 1];
-        for x in (1..grid_size - 1).rev() {
-            if trees[y][x] > current_heighest {
-                visible.insert((x, y));
-                current_heighest = trees[y][x]
+fn look_from_left(trees: &Grid<u32>, visible: &mut HashSet<Position>) {
+    for y in 1..trees.height() - 1 {
+        let mut current_heighest = trees[Position::new(0, y)];
+        for x in 1..trees.width() - 1 {
+            let position = Position::new(x, y);
+            if trees[position] > current_heighest {
+                visible.insert(position);
+                current_heighest = trees[position]
             }
         }
     }
 }
 
-fn look_from_top(trees: &[Vec<u32>], visible: &mut HashSet<(usize, usize)>) {
-    let grid_size = trees.len();
-    for x in 1..grid_size - 1 {
-        let mut current_heighest = trees[0][x];
-        for y in 1..grid_size - 1 {
-            if trees[y][x] > current_heighest {
-                visible.insert((x, y));
-                current_heighest = trees[y][x];
+fn look_from_right(trees: &Grid<u32>, visible: &mut HashSet<Position>) {
+    for y in 1..trees.height() - 1 {
+        let mut current_heighest = trees[Position::new(trees.width() - 1, y)];
+        for x in (1..trees.width() - 1).rev() {
+            let position = Position::new(x, y);
+            if trees[position] > current_heighest {
+                visible.insert(position);
+                current_heighest = trees[position]
             }
         }
     }
 }
 
-fn look_from_bottom(trees: &[Vec<u32>], visible: &mut HashSet<(usize, usize)>) {
-    let grid_size = trees.len();
-    for x in 1..grid_size - 1 {
-        let mut current_heighest = trees[grid_size - 1][x];
-        for y in (1..grid_size - 1).rev() {
-            if trees[y][x] > current_heighest {
-                visible.insert((x, y));
-                current_heighest = trees[y][x];
+fn look_from_top(trees: &Grid<u32>, visible: &mut HashSet<Position>) {
+    for x in 1..trees.width() - 1 {
+        let mut current_heighest = trees[Position::new(x, 0)];
+        for y in 1..trees.height() - 1 {
+            let position = Position::new(x, y);
+            if trees[position] > current_heighest {
+                visible.insert(position);
+                current_heighest = trees[position];
             }
         }
     }
 }
 
-fn look_right(trees: &[Vec<u32>], init_x: usize, y: usize) -> usize {
-    let grid_size = trees.len();
-    (init_x + 1..grid_size)
-        .enumerate()
-        .find(|(_, x)| trees[y][*x] >= trees[y][init_x])
-        .map(|(count, _)| count + 1)
-        .unwrap_or(grid_size - init_x - 1)
+fn look_from_bottom(trees: &Grid<u32>, visible: &mut HashSet<Position>) {
+    for x in 1..trees.width() - 1 {
+        let mut current_heighest = trees[Position::new(x, trees.height() - 1)];
+        for y in (1..trees.height() - 1).rev() {
+            let position = Position::new(x, y);
+            if trees[position] > current_heighest {
+                visible.insert(position);
+                current_heighest = trees[position];
+            }
+        }
+    }
 }
 
-fn look_left(trees: &[Vec<u32>], init_x: usize, y: usize) -> usize {
-    (0..=init_x - 1)
-        .rev()
-        .enumerate()
-        .find(|(_, x)| trees[y][*x] >= trees[y][init_x])
-        .map(|(count, _)| count + 1)
-        .unwrap_or(init_x)
+fn look_right(trees: &Grid<u32>, position: Position) -> usize {
+    look(trees, position, (1, 0))
 }
 
-fn look_up(trees: &[Vec<u32>], init_y: usize, x: usize) -> usize {
-    (0..=init_y - 1)
-        .rev()
-        .enumerate()
-        .find(|(_, y)| trees[*y][x] >= trees[init_y][x])
-        .map(|(count, _)| count + 1)
-        .unwrap_or(init_y)
+fn look_left(trees: &Grid<u32>, position: Position) -> usize {
+    look(trees, position, (-1, 0))
+}
+
+fn look_up(trees: &Grid<u32>, position: Position) -> usize {
+    look(trees, position, (0, -1))
+}
+
+fn look_down(trees: &Grid<u32>, position: Position) -> usize {
+    look(trees, position, (0, 1))
 }
 
-fn look_down(trees: &[Vec<u32>], init_y: usize, x: usize) -> usize {
-    let grid_size = trees.len();
-    (init_y + 1..grid_size)
+fn look(trees: &Grid<u32>, position: Position, direction: (isize, isize)) -> usize {
+    trees
+        .ray(position, direction)
         .enumerate()
-        .find(|(_, y)| trees[*y][x] >= trees[init_y][x])
+        .find(|(_, p)| trees[*p] >= trees[position])
         .map(|(count, _)| count + 1)
-        .unwrap_or(grid_size - init_y - 1)
+        .unwrap_or_else(|| trees.ray(position, direction).count())
+}
+
+fn export(trees: &Grid<u32>, dir: &Path) -> io::Result<()> {
+    let visibility = visibility_mask(trees);
+    let scores = scenic_scores(trees);
+    let max_score = scores.iter().map(|(_, score)| *score).max().unwrap_or(0);
+
+    export::write_csv(&visibility.map(|v| *v as u8), dir.join("visibility.csv"))?;
+    export::write_csv(&scores, dir.join("scenic_scores.csv"))?;
+    export::write_ppm(&visibility, dir.join("visibility.ppm"), |v| {
+        if *v {
+            [46, 160, 67]
+        } else {
+            [20, 20, 20]
+        }
+    })?;
+    export::write_ppm(&scores, dir.join("scenic_scores.ppm"), |score| {
+        export::heat(*score, max_score)
+    })
 }
 
 fn main() {
+    let example = Grid::parse(EXAMPLE, |c| c.to_digit(10).unwrap());
+    assert_eq!(part_1(&example), 21);
+    assert_eq!(
+        visibility_mask(&example)
+            .find_all(|&visible| !visible)
+            .collect::<Vec<_>>(),
+        [
+            Position::new(3, 1),
+            Position::new(2, 2),
+            Position::new(1, 3),
+            Position::new(3, 3)
+        ]
+    );
+    assert_eq!(part_2(&example), 8);
+
+    let rectangular = Grid::parse(&EXAMPLE[..17], |c| c.to_digit(10).unwrap());
+    assert_eq!(part_1(&rectangular), 14);
+    assert_eq!(part_2(&rectangular), part_2_reference(&rectangular));
+
     let input = read_to_string("input").unwrap();
-    let trees = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap())
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+    let trees = Grid::parse(&input, |c| c.to_digit(10).unwrap());
 
     assert_eq!(part_1(&trees), 1703);
     assert_eq!(part_2(&trees), 496650);
+    assert_eq!(part_2_reference(&trees), 496650);
+
+    if let Some(dir) = env::args().nth(1) {
+        export(&trees, Path::new(&dir)).unwrap();
+    }
 }
@@ -1,4 +1,31 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, env, fs};
+
+const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+
+const LARGER_EXAMPLE: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20";
+
+const EXAMPLE_VISITED: &str = "..##..
+...##.
+.####.
+....#.
+s###..
+";
+
+type Bounds = ((i32, i32), (i32, i32));
 
 fn part_1(input: &str) -> usize {
     let mut rope = Rope::with_knots(2);
@@ -11,25 +38,90 @@ fn part_2(input: &str) -> usize {
 }
 
 fn process_motions(input: &str, rope: &mut Rope) -> usize {
+    visited_by_tail(input, rope).len()
+}
+
+fn visited_by_tail(input: &str, rope: &mut Rope) -> HashSet<(i32, i32)> {
     let mut visited = HashSet::new();
+    simulate(input, rope, |rope| {
+        visited.insert(*rope.knots.last().unwrap());
+    });
+    visited
+}
+
+/// Positions of all knots at the start and after every single step.
+fn trace_motions(input: &str, rope: &mut Rope) -> Vec<Vec<(i32, i32)>> {
+    let mut frames = vec![rope.knots.clone()];
+    simulate(input, rope, |rope| frames.push(rope.knots.clone()));
+    frames
+}
+
+fn simulate(input: &str, rope: &mut Rope, mut on_step: impl FnMut(&Rope)) {
     for line in input.lines() {
         let mut parts = line.split_whitespace();
         let direction = parts.next().unwrap();
         let count = parts.next().unwrap().parse::<i32>().unwrap();
 
+        let (dx, dy) = match direction {
+            "R" => (1, 0),
+            "L" => (-1, 0),
+            "U" => (0, 1),
+            "D" => (0, -1),
+            "UR" => (1, 1),
+            "UL" => (-1, 1),
+            "DR" => (1, -1),
+            "DL" => (-1, -1),
+            _ => panic!("unknown direction {direction}"),
+        };
         for _ in 0..count {
-            match direction {
-                "R" => rope.move_right(),
-                "L" => rope.move_left(),
-                "U" => rope.move_up(),
-                "D" => rope.move_down(),
-                _ => panic!(),
-            }
-            visited.insert(*rope.knots.last().unwrap());
+            rope.apply_motion(dx, dy, 1);
+            on_step(rope);
         }
     }
+}
+
+fn bounds<'a>(positions: impl IntoIterator<Item = &'a (i32, i32)>) -> Bounds {
+    positions.into_iter().fold(
+        ((0, 0), (0, 0)),
+        |((min_x, min_y), (max_x, max_y)), &(x, y)| {
+            ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+        },
+    )
+}
+
+fn render(bounds: Bounds, symbol_at: impl Fn((i32, i32)) -> Option<char>) -> String {
+    let ((min_x, min_y), (max_x, max_y)) = bounds;
+    let mut rendered = String::new();
+    for y in (min_y..=max_y).rev() {
+        for x in min_x..=max_x {
+            rendered.push(symbol_at((x, y)).unwrap_or('.'));
+        }
+        rendered.push('\n');
+    }
+    rendered
+}
 
-    visited.len()
+/// Draws a single step the way the puzzle does: `H`, then `T` or knot numbers, then `s`.
+fn render_frame(knots: &[(i32, i32)], bounds: Bounds) -> String {
+    render(bounds, |position| {
+        let label = match knots.iter().position(|knot| *knot == position) {
+            Some(0) => 'H',
+            Some(_) if knots.len() == 2 => 'T',
+            Some(idx) => char::from_digit(idx as u32, 36).unwrap(),
+            None => return (position == (0, 0)).then_some('s'),
+        };
+        Some(label)
+    })
+}
+
+fn render_visited(visited: &HashSet<(i32, i32)>, bounds: Bounds) -> String {
+    render(bounds, |position| {
+        if position == (0, 0) {
+            Some('s')
+        } else {
+            visited.contains(&position).then_some('#')
+        }
+    })
 }
 
 #[derive(Default, Debug)]
@@ -43,55 +135,67 @@ impl Rope {
             knots: vec![(0, 0); size],
         }
     }
-    fn move_right(&mut self) {
-        self.knots[0].0 += 1;
-        for i in 1..self.knots.len() {
-            self.move_knot_if_needed(i);
-        }
-    }
-
-    fn move_left(&mut self) {
-        self.knots[0].0 -= 1;
-        for i in 1..self.knots.len() {
-            self.move_knot_if_needed(i);
-        }
-    }
-
-    fn move_up(&mut self) {
-        self.knots[0].1 += 1;
-        for i in 1..self.knots.len() {
-            self.move_knot_if_needed(i);
-        }
-    }
 
-    fn move_down(&mut self) {
-        self.knots[0].1 -= 1;
-        for i in 1..self.knots.len() {
-            self.move_knot_if_needed(i);
+    /// Moves the head by `(dx, dy)` `count` times, letting the other knots catch up after
+    /// every move. Moves longer than one cell make the followers take several steps.
+    fn apply_motion(&mut self, dx: i32, dy: i32, count: usize) {
+        for _ in 0..count {
+            self.knots[0].0 += dx;
+            self.knots[0].1 += dy;
+            for i in 1..self.knots.len() {
+                self.move_knot_if_needed(i);
+            }
         }
     }
 
     fn move_knot_if_needed(&mut self, idx: usize) {
-        let v = (
-            self.knots[idx - 1].0 - self.knots[idx].0,
-            self.knots[idx - 1].1 - self.knots[idx].1,
-        );
-        let length = f64::from(v.0.pow(2) + v.1.pow(2)).sqrt().round() as i32;
-        if length > 1 {
-            let off = (
-                std::cmp::min(1, v.0.abs()) * v.0.signum(),
-                std::cmp::min(1, v.1.abs()) * v.1.signum(),
+        loop {
+            let v = (
+                self.knots[idx - 1].0 - self.knots[idx].0,
+                self.knots[idx - 1].1 - self.knots[idx].1,
             );
+            if v.0.abs().max(v.1.abs()) <= 1 {
+                break;
+            }
 
-            self.knots[idx].0 += off.0;
-            self.knots[idx].1 += off.1;
+            self.knots[idx].0 += v.0.signum();
+            self.knots[idx].1 += v.1.signum();
         }
     }
 }
 
 fn main() {
+    assert_eq!(part_1(EXAMPLE), 13);
+    assert_eq!(part_2(LARGER_EXAMPLE), 36);
+    let frames = trace_motions(EXAMPLE, &mut Rope::with_knots(2));
+    let example_bounds = bounds(frames.iter().flatten());
+    assert_eq!(frames.len(), 25);
+    assert_eq!(
+        render_frame(frames.last().unwrap(), example_bounds),
+        "......\n......\n.TH...\n......\ns.....\n"
+    );
+    assert_eq!(
+        render_visited(
+            &visited_by_tail(EXAMPLE, &mut Rope::with_knots(2)),
+            example_bounds
+        ),
+        EXAMPLE_VISITED
+    );
+
+    let mut rope = Rope::with_knots(3);
+    rope.apply_motion(1, 1, 2);
+    assert_eq!(rope.knots, vec![(2, 2), (1, 1), (0, 0)]);
+    rope.apply_motion(4, 0, 1);
+    assert_eq!(rope.knots, vec![(6, 2), (5, 2), (4, 2)]);
+    assert_eq!(process_motions("UR 3\nD 2", &mut Rope::with_knots(2)), 3);
+
     let input = std::fs::read_to_string("input").unwrap();
 
     assert_eq!(part_1(&input), 6391);
     assert_eq!(part_2(&input), 2593);
+
+    if let Some(path) = env::args().nth(1) {
+        let visited = visited_by_tail(&input, &mut Rope::with_knots(10));
+        fs::write(path, render_visited(&visited, bounds(&visited))).unwrap();
+    }
 }
@@ -0,0 +1,278 @@
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Register {
+    X,
+    Y,
+}
+
+impl Register {
+    pub const COUNT: usize = 2;
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "x" | "X" => Some(Register::X),
+            "y" | "Y" => Some(Register::Y),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Noop,
+    Add(Register),
+}
+
+#[derive(Debug)]
+pub struct OpSpec {
+    pub mnemonic: &'static str,
+    pub op: Op,
+    pub cycles: u32,
+    pub has_operand: bool,
+}
+
+pub const INSTRUCTION_SET: [OpSpec; 3] = [
+    OpSpec {
+        mnemonic: "noop",
+        op: Op::Noop,
+        cycles: 1,
+        has_operand: false,
+    },
+    OpSpec {
+        mnemonic: "addx",
+        op: Op::Add(Register::X),
+        cycles: 2,
+        has_operand: true,
+    },
+    OpSpec {
+        mnemonic: "addy",
+        op: Op::Add(Register::Y),
+        cycles: 2,
+        has_operand: true,
+    },
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub op: Op,
+    pub operand: i32,
+}
+
+impl Instruction {
+    pub const NOOP: Self = Self {
+        op: Op::Noop,
+        operand: 0,
+    };
+
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut parts = line.split_ascii_whitespace();
+        let mnemonic = parts.next().ok_or("empty instruction")?;
+        let spec = INSTRUCTION_SET
+            .iter()
+            .find(|spec| spec.mnemonic == mnemonic)
+            .ok_or_else(|| format!("unknown instruction {mnemonic}"))?;
+        let operand = if spec.has_operand {
+            let operand = parts
+                .next()
+                .ok_or_else(|| format!("{mnemonic} needs an operand"))?;
+            operand
+                .parse()
+                .map_err(|_| format!("invalid operand {operand}"))?
+        } else {
+            0
+        };
+        if let Some(extra) = parts.next() {
+            return Err(format!("unexpected {extra} after {mnemonic}"));
+        }
+
+        Ok(Self {
+            op: spec.op,
+            operand,
+        })
+    }
+
+    pub fn spec(&self) -> &'static OpSpec {
+        INSTRUCTION_SET
+            .iter()
+            .find(|spec| spec.op == self.op)
+            .unwrap()
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let spec = self.spec();
+        if spec.has_operand {
+            write!(f, "{} {}", spec.mnemonic, self.operand)
+        } else {
+            write!(f, "{}", spec.mnemonic)
+        }
+    }
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, String> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Instruction::parse(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect()
+}
+
+/// Like `parse_program`, but skips blank lines and `;` comments.
+pub fn assemble(source: &str) -> Result<Vec<Instruction>, String> {
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.split(';').next().unwrap().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| Instruction::parse(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect()
+}
+
+/// One instruction per line, prefixed with its address.
+pub fn disassemble(program: &[Instruction]) -> String {
+    program
+        .iter()
+        .enumerate()
+        .map(|(address, instruction)| format!("{address:04}: {instruction}\n"))
+        .collect()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Registers([i32; Register::COUNT]);
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self([1, 0])
+    }
+}
+
+impl Registers {
+    pub fn get(&self, register: Register) -> i32 {
+        self.0[register as usize]
+    }
+
+    fn add(&mut self, register: Register, value: i32) {
+        self.0[register as usize] += value;
+    }
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "X={} Y={}", self.get(Register::X), self.get(Register::Y))
+    }
+}
+
+/// Called once per cycle with the register values *during* that cycle.
+pub trait Observer {
+    fn on_cycle(&mut self, cycle: u32, registers: &Registers, instruction: &Instruction);
+}
+
+impl<F: FnMut(u32, &Registers, &Instruction)> Observer for F {
+    fn on_cycle(&mut self, cycle: u32, registers: &Registers, instruction: &Instruction) {
+        self(cycle, registers, instruction)
+    }
+}
+
+/// Records a line per cycle: cycle number, registers and the instruction in flight.
+#[derive(Default)]
+pub struct Tracer {
+    pub lines: Vec<String>,
+}
+
+impl Observer for Tracer {
+    fn on_cycle(&mut self, cycle: u32, registers: &Registers, instruction: &Instruction) {
+        self.lines
+            .push(format!("{cycle:>4} | {registers} | {instruction}"));
+    }
+}
+
+#[derive(Debug)]
+pub struct Cpu {
+    program: Vec<Instruction>,
+    pc: usize,
+    cycle: u32,
+    registers: Registers,
+    in_flight: Option<(Instruction, u32)>,
+}
+
+impl Cpu {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            program,
+            pc: 0,
+            cycle: 0,
+            registers: Registers::default(),
+            in_flight: None,
+        }
+    }
+
+    /// Number of completed cycles.
+    pub fn cycle(&self) -> u32 {
+        self.cycle
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// Address of the next instruction to fetch.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The instruction being executed and the cycles it still needs.
+    pub fn in_flight(&self) -> Option<(Instruction, u32)> {
+        self.in_flight
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.in_flight.is_none() && self.pc >= self.program.len()
+    }
+
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+
+    /// Executes a single cycle. Once the program ends the CPU keeps executing `noop`, with `pc`
+    /// left just past the last instruction.
+    pub fn step(&mut self, observer: &mut impl Observer) {
+        let (instruction, remaining) = match self.in_flight.take() {
+            Some(in_flight) => in_flight,
+            None => {
+                let instruction = self
+                    .program
+                    .get(self.pc)
+                    .copied()
+                    .unwrap_or(Instruction::NOOP);
+                self.pc = (self.pc + 1).min(self.program.len());
+                (instruction, instruction.spec().cycles)
+            }
+        };
+
+        self.cycle += 1;
+        observer.on_cycle(self.cycle, &self.registers, &instruction);
+
+        if remaining > 1 {
+            self.in_flight = Some((instruction, remaining - 1));
+        } else if let Op::Add(register) = instruction.op {
+            self.registers.add(register, instruction.operand);
+        }
+    }
+
+    pub fn run_until(&mut self, cycle: u32, observer: &mut impl Observer) {
+        while self.cycle < cycle {
+            self.step(observer);
+        }
+    }
+
+    /// Runs up to and including `cycle`, returning X during that cycle.
+    pub fn execute_next_cycles(&mut self, cycle: u32) -> i32 {
+        let mut x = self.registers.get(Register::X);
+        self.run_until(cycle, &mut |_, registers: &Registers, _: &Instruction| {
+            x = registers.get(Register::X)
+        });
+        x
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{
+    cpu::{self, Cpu, Instruction, Register, Registers},
+    Screen,
+};
+use std::{
+    fmt, fs,
+    io::{self, BufRead, Write},
+};
+
+const HELP: &str = "commands:
+  load <path>               load a program, resetting the CPU and the screen
+  step [n]                  execute n cycles (default 1), stopping at breakpoints
+  continue                  run until a breakpoint is hit or the program ends
+  break cycle <n>           stop before cycle n starts
+  break <register> <value>  stop when a cycle changes the register to the value
+  delete                    remove all breakpoints
+  breakpoints               list breakpoints
+  regs                      show cycle, registers and the instruction in flight
+  crt                       show the CRT row being drawn and the sprite
+  screen                    show the whole screen
+  disasm                    list the program
+  quit                      leave the debugger";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    Cycle(u32),
+    Register(Register, i32),
+}
+
+impl Breakpoint {
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        match args {
+            ["cycle", cycle] => cycle
+                .parse()
+                .map(Breakpoint::Cycle)
+                .map_err(|_| format!("invalid cycle {cycle}")),
+            [register, value] => {
+                let register = Register::from_name(register)
+                    .ok_or_else(|| format!("unknown register {register}"))?;
+                let value = value
+                    .parse()
+                    .map_err(|_| format!("invalid value {value}"))?;
+                Ok(Breakpoint::Register(register, value))
+            }
+            _ => Err("usage: break cycle <n> | break <register> <value>".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Cycle(cycle) => write!(f, "cycle {cycle}"),
+            Breakpoint::Register(register, value) => write!(f, "{register:?}={value}"),
+        }
+    }
+}
+
+pub struct Debugger {
+    cpu: Cpu,
+    screen: Screen,
+    breakpoints: Vec<Breakpoint>,
+    /// The breakpoint execution last stopped at, skipped once when resuming.
+    stopped_at: Option<Breakpoint>,
+}
+
+impl Debugger {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            cpu: Cpu::new(program),
+            screen: Screen::default(),
+            breakpoints: vec![],
+            stopped_at: None,
+        }
+    }
+
+    pub fn execute(&mut self, command: &str) -> Result<String, String> {
+        let args = command.split_ascii_whitespace().collect::<Vec<_>>();
+        match args.as_slice() {
+            ["load", path] => {
+                let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+                let program = cpu::assemble(&source)?;
+                let loaded = format!("loaded {} instructions", program.len());
+                self.cpu = Cpu::new(program);
+                self.screen = Screen::default();
+                self.stopped_at = None;
+                Ok(loaded)
+            }
+            ["step"] => Ok(self.run(Some(1))),
+            ["step", count] => {
+                let count = count
+                    .parse()
+                    .map_err(|_| format!("invalid count {count}"))?;
+                Ok(self.run(Some(count)))
+            }
+            ["continue"] | ["c"] => Ok(self.run(None)),
+            ["break", args @ ..] => {
+                let breakpoint = Breakpoint::parse(args)?;
+                self.breakpoints.push(breakpoint);
+                Ok(format!("breakpoint set at {breakpoint}"))
+            }
+            ["delete"] => {
+                self.breakpoints.clear();
+                Ok("breakpoints removed".to_string())
+            }
+            ["breakpoints"] => Ok(self
+                .breakpoints
+                .iter()
+                .map(|breakpoint| breakpoint.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")),
+            ["regs"] => Ok(self.status()),
+            ["crt"] => Ok(self.crt_row()),
+            ["screen"] => Ok(self.screen.to_string().trim_end().to_string()),
+            ["disasm"] => Ok(cpu::disassemble(self.cpu.program()).trim_end().to_string()),
+            ["help"] => Ok(HELP.to_string()),
+            _ => Err(format!("unknown command {command:?}, try help")),
+        }
+    }
+
+    /// Runs `max_cycles` cycles, or until the program ends if `None`. Cycle breakpoints are
+    /// checked before every cycle, except the one execution just stopped at so that running
+    /// again resumes past it. Register breakpoints fire when a cycle changes the register to
+    /// the value.
+    fn run(&mut self, max_cycles: Option<u32>) -> String {
+        let mut executed = 0;
+        loop {
+            if max_cycles.is_some_and(|max| executed == max) {
+                return self.status();
+            }
+            let resuming = executed == 0;
+            if let Some(breakpoint) = self
+                .hit_cycle_breakpoint()
+                .filter(|&breakpoint| !(resuming && self.stopped_at == Some(breakpoint)))
+            {
+                return self.stop_at(breakpoint);
+            }
+            if max_cycles.is_none() && self.cpu.is_halted() {
+                return format!("program finished\n{}", self.status());
+            }
+
+            let before = *self.cpu.registers();
+            let screen = &mut self.screen;
+            self.cpu
+                .step(&mut |cycle, registers: &Registers, _: &Instruction| {
+                    screen.draw_cycle(cycle, registers.get(Register::X))
+                });
+            executed += 1;
+            self.stopped_at = None;
+
+            if let Some(breakpoint) = self.hit_register_breakpoint(&before) {
+                return self.stop_at(breakpoint);
+            }
+        }
+    }
+
+    fn stop_at(&mut self, breakpoint: Breakpoint) -> String {
+        self.stopped_at = Some(breakpoint);
+        format!("hit breakpoint {breakpoint}\n{}", self.status())
+    }
+
+    fn hit_cycle_breakpoint(&self) -> Option<Breakpoint> {
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| **breakpoint == Breakpoint::Cycle(self.cpu.cycle() + 1))
+            .copied()
+    }
+
+    fn hit_register_breakpoint(&self, before: &Registers) -> Option<Breakpoint> {
+        let after = self.cpu.registers();
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| match breakpoint {
+                Breakpoint::Cycle(_) => false,
+                Breakpoint::Register(register, value) => {
+                    before.get(*register) != *value && after.get(*register) == *value
+                }
+            })
+            .copied()
+    }
+
+    fn status(&self) -> String {
+        let in_flight = match self.cpu.in_flight() {
+            Some((instruction, remaining)) => {
+                format!("{instruction} ({remaining} cycles left)")
+            }
+            None => "none".to_string(),
+        };
+        let pc = if self.cpu.is_halted() {
+            "halted".to_string()
+        } else {
+            format!("pc {:04}", self.cpu.pc())
+        };
+        format!(
+            "cycle {} | {} | {pc} | in flight: {in_flight}",
+            self.cpu.cycle(),
+            self.cpu.registers(),
+        )
+    }
+
+    fn crt_row(&self) -> String {
+        let row = self.cpu.cycle() as usize / Screen::WIDTH % Screen::HEIGHT;
+        let x = self.cpu.registers().get(Register::X);
+        let sprite = (0..Screen::WIDTH as i32)
+            .map(|pixel| if (pixel - x).abs() <= 1 { '#' } else { '.' })
+            .collect::<String>();
+        format!("row {row}:  {}\nsprite: {sprite}", self.screen.row(row))
+    }
+}
+
+pub fn repl(mut debugger: Debugger, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    write!(output, "(debug) ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        match line.trim() {
+            "quit" | "q" => break,
+            "" => (),
+            command => match debugger.execute(command) {
+                Ok(response) => writeln!(output, "{response}")?,
+                Err(error) => writeln!(output, "error: {error}")?,
+            },
+        }
+        write!(output, "(debug) ")?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
@@ -1,92 +1,160 @@
-use std::collections::VecDeque;
+mod cpu;
+mod debugger;
+mod ocr;
 
-fn part_1(program: VecDeque<Instruction>) -> i32 {
+use cpu::{Cpu, Instruction, Register, Registers, Tracer};
+use debugger::Debugger;
+use std::{env, fmt, io};
+
+const SMALL_EXAMPLE: &str = "noop
+addx 3
+addx -5";
+
+fn part_1(program: Vec<Instruction>) -> i32 {
     let mut cpu = Cpu::new(program);
-    (20..=220)
-        .step_by(40)
-        .map(|c| cpu.execute_next_cycles(c) * c as i32)
-        .sum()
+    let mut signal_strength = 0;
+    cpu.run_until(220, &mut |cycle, registers: &Registers, _: &Instruction| {
+        if cycle % 40 == 20 {
+            signal_strength += cycle as i32 * registers.get(Register::X);
+        }
+    });
+    signal_strength
 }
 
-fn part_2(program: VecDeque<Instruction>) {
+fn part_2(program: Vec<Instruction>) -> String {
+    ocr::recognise(&draw(program)).unwrap()
+}
+
+fn draw(program: Vec<Instruction>) -> Screen {
     let mut cpu = Cpu::new(program);
 
-    let mut screen = vec![vec!['.'; 40]; 6];
-    for row in 0..6 {
-        for pixel in 0..40 {
-            let sprite_position = cpu.execute_next_cycles((pixel + 1) + 40 * row);
+    let mut screen = Screen::default();
+    let pixel_count = (Screen::WIDTH * Screen::HEIGHT) as u32;
+    cpu.run_until(
+        pixel_count,
+        &mut |cycle, registers: &Registers, _: &Instruction| {
+            screen.draw_cycle(cycle, registers.get(Register::X))
+        },
+    );
 
-            if pixel as i32 >= sprite_position - 1 && pixel as i32 <= sprite_position + 1 {
-                screen[row as usize][(pixel) as usize] = '#';
-            }
-        }
+    screen
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Screen {
+    pixels: [[bool; Screen::WIDTH]; Screen::HEIGHT],
+}
+
+impl Screen {
+    const WIDTH: usize = 40;
+    const HEIGHT: usize = 6;
+
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        self.pixels[y][x]
     }
 
-    for row in screen.iter() {
-        for c in row {
-            print!("{c}");
+    /// Draws the pixel the CRT is at during `cycle` if the sprite covers it.
+    fn draw_cycle(&mut self, cycle: u32, sprite_position: i32) {
+        let (row, pixel) = (
+            (cycle as usize - 1) / Screen::WIDTH % Screen::HEIGHT,
+            (cycle as usize - 1) % Screen::WIDTH,
+        );
+        if pixel as i32 >= sprite_position - 1 && pixel as i32 <= sprite_position + 1 {
+            self.pixels[row][pixel] = true;
         }
-        println!();
     }
-}
-
-#[derive(Copy, Clone, Debug)]
-enum Instruction {
-    Add(u32, i32),
-    Noop,
-}
 
-#[derive(Debug)]
-struct Cpu {
-    program: VecDeque<Instruction>,
-    current_cycle: u32,
-    register: i32,
+    fn row(&self, y: usize) -> String {
+        self.pixels[y]
+            .iter()
+            .map(|lit| if *lit { '#' } else { '.' })
+            .collect()
+    }
 }
 
-impl Cpu {
-    fn new(program: VecDeque<Instruction>) -> Self {
+impl Default for Screen {
+    fn default() -> Self {
         Self {
-            program,
-            current_cycle: 0,
-            register: 1,
+            pixels: [[false; Screen::WIDTH]; Screen::HEIGHT],
         }
     }
+}
 
-    fn execute_next_cycles(&mut self, count: u32) -> i32 {
-        let mut register = self.register;
-        for _ in self.current_cycle..count {
-            register = self.register;
-            let instruction = self.program.pop_front().unwrap_or(Instruction::Noop);
-            if let Instruction::Add(cycles, value) = instruction {
-                if cycles == 1 {
-                    self.register += value;
-                } else {
-                    self.program.push_front(Instruction::Add(1, value));
-                }
-            }
-
-            self.current_cycle += 1;
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..Screen::HEIGHT {
+            writeln!(f, "{}", self.row(y))?;
         }
-        register
+        Ok(())
     }
 }
 
 fn main() {
+    let small_program = cpu::parse_program(SMALL_EXAMPLE).unwrap();
+    assert_eq!(
+        cpu::disassemble(&small_program),
+        "0000: noop\n0001: addx 3\n0002: addx -5\n"
+    );
+    let mut tracer = Tracer::default();
+    Cpu::new(small_program.clone()).run_until(5, &mut tracer);
+    assert_eq!(tracer.lines[3], "   4 | X=4 Y=0 | addx -5");
+    assert_eq!(Cpu::new(small_program).execute_next_cycles(6), -1);
+    assert!(cpu::parse_program("addx").is_err());
+    assert_eq!(
+        cpu::assemble("; increments\nnoop\n\naddx 3 ; by three\naddx -5").unwrap(),
+        cpu::parse_program(SMALL_EXAMPLE).unwrap()
+    );
+
+    let mut debugger = Debugger::new(cpu::parse_program(SMALL_EXAMPLE).unwrap());
+    debugger.execute("break x 4").unwrap();
+    assert_eq!(
+        debugger.execute("continue").unwrap(),
+        "hit breakpoint X=4\ncycle 3 | X=4 Y=0 | pc 0002 | in flight: none"
+    );
+    assert!(debugger
+        .execute("continue")
+        .unwrap()
+        .starts_with("program finished"));
+
+    let mut debugger = Debugger::new(cpu::parse_program(SMALL_EXAMPLE).unwrap());
+    debugger.execute("break cycle 1").unwrap();
+    debugger.execute("break cycle 4").unwrap();
+    assert_eq!(
+        debugger.execute("continue").unwrap(),
+        "hit breakpoint cycle 1\ncycle 0 | X=1 Y=0 | pc 0000 | in flight: none"
+    );
+    assert_eq!(
+        debugger.execute("step").unwrap(),
+        "cycle 1 | X=1 Y=0 | pc 0001 | in flight: none"
+    );
+    assert_eq!(
+        debugger.execute("continue").unwrap(),
+        "hit breakpoint cycle 4\ncycle 3 | X=4 Y=0 | pc 0002 | in flight: none"
+    );
+    assert_eq!(
+        debugger.execute("step").unwrap(),
+        "cycle 4 | X=4 Y=0 | pc 0003 | in flight: addx -5 (1 cycles left)"
+    );
+    assert!(debugger
+        .execute("continue")
+        .unwrap()
+        .starts_with("program finished"));
+    assert_eq!(
+        debugger.execute("step 3").unwrap(),
+        "cycle 8 | X=-1 Y=0 | halted | in flight: none"
+    );
+    assert!(debugger.execute("break z 1").is_err());
+
     let input = std::fs::read_to_string("input").unwrap();
 
-    let program = input
-        .lines()
-        .map(|line| {
-            let mut parts = line.split_ascii_whitespace();
-            let instruction = parts.next().unwrap();
-            match instruction {
-                "addx" => Instruction::Add(2, parts.next().unwrap().parse::<i32>().unwrap()),
-                "noop" => Instruction::Noop,
-                _ => panic!("unknown instruction"),
-            }
-        })
-        .collect::<VecDeque<_>>();
+    let program = cpu::parse_program(&input).unwrap();
 
     assert_eq!(part_1(program.clone()), 12880);
-    part_2(program);
+    assert_eq!(part_2(program), "FCJAPJRE");
+
+    if env::args().nth(1).as_deref() == Some("debug") {
+        let path = env::args().nth(2).unwrap_or_else(|| "input".to_string());
+        let program = cpu::assemble(&std::fs::read_to_string(path).unwrap()).unwrap();
+        debugger::repl(Debugger::new(program), io::stdin().lock(), io::stdout()).unwrap();
+    }
 }
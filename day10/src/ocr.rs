@@ -0,0 +1,43 @@
+use crate::Screen;
+
+const LETTER_WIDTH: usize = 4;
+const LETTER_SPACING: usize = 1;
+
+const FONT: [(char, [&str; 6]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters drawn on the screen, `None` if any of them is not in the font.
+pub fn recognise(screen: &Screen) -> Option<String> {
+    (0..Screen::WIDTH / (LETTER_WIDTH + LETTER_SPACING))
+        .map(|i| recognise_letter(screen, i * (LETTER_WIDTH + LETTER_SPACING)))
+        .collect()
+}
+
+fn recognise_letter(screen: &Screen, offset: usize) -> Option<char> {
+    FONT.iter()
+        .find(|(_, glyph)| {
+            glyph.iter().enumerate().all(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .all(|(x, c)| (c == '#') == screen.is_lit(offset + x, y))
+            })
+        })
+        .map(|(letter, _)| *letter)
+}
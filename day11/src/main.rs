@@ -1,9 +1,52 @@
-use std::collections::VecDeque;
+mod trace;
+
+use std::{collections::VecDeque, env, fmt, fs, path::Path};
+use trace::Trace;
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add(u64),
+    Multiply(u64),
+    Square,
+}
+
+impl Operation {
+    fn apply(self, old: u64) -> Option<u64> {
+        match self {
+            Operation::Add(value) => old.checked_add(value),
+            Operation::Multiply(value) => old.checked_mul(value),
+            Operation::Square => old.checked_mul(old),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Overflow {
+    monkey: usize,
+    round: usize,
+    item: u64,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "monkey {} overflowed inspecting item {} in round {}",
+            self.monkey, self.item, self.round
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Item {
+    id: usize,
+    worry_level: u64,
+}
 
 #[derive(Clone)]
 struct Monkey {
-    items: VecDeque<u64>,
-    operation: fn(u64) -> u64,
+    items: VecDeque<Item>,
+    operation: Operation,
     test: u64,
     when_true: usize,
     when_false: usize,
@@ -13,13 +56,16 @@ struct Monkey {
 impl Monkey {
     fn new(
         items: VecDeque<u64>,
-        operation: fn(u64) -> u64,
+        operation: Operation,
         test: u64,
         when_true: usize,
         when_false: usize,
     ) -> Self {
         Self {
-            items,
+            items: items
+                .into_iter()
+                .map(|worry_level| Item { id: 0, worry_level })
+                .collect(),
             operation,
             test,
             when_true,
@@ -30,87 +76,216 @@ impl Monkey {
 }
 
 #[derive(Clone)]
-struct Monkeys(Vec<Monkey>);
+struct Monkeys {
+    monkeys: Vec<Monkey>,
+    rounds_played: usize,
+    trace: Option<Trace>,
+}
 
 impl Monkeys {
-    fn play_round(&mut self, after_inspect: fn(u64) -> u64) {
+    fn new(mut monkeys: Vec<Monkey>) -> Self {
+        let items = monkeys
+            .iter_mut()
+            .flat_map(|monkey| monkey.items.iter_mut());
+        for (id, item) in items.enumerate() {
+            item.id = id;
+        }
+
+        Self {
+            monkeys,
+            rounds_played: 0,
+            trace: None,
+        }
+    }
+
+    /// Records inspections per round and the path of every item from now on.
+    fn with_trace(mut self) -> Self {
+        let mut trace = Trace::default();
+        for (monkey_idx, monkey) in self.monkeys.iter().enumerate() {
+            for item in monkey.items.iter() {
+                trace.hand_over(item.id, self.rounds_played, monkey_idx);
+            }
+        }
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Product of all divisibility tests; worry levels can be kept modulo it.
+    fn common_modulus(&self) -> u64 {
+        self.monkeys.iter().map(|monkey| monkey.test).product()
+    }
+
+    fn inspections(&self) -> Vec<u64> {
+        self.monkeys
+            .iter()
+            .map(|monkey| monkey.inspected_items)
+            .collect()
+    }
+
+    fn monkey_business(&self) -> u64 {
+        let mut inspections = self.inspections();
+        inspections.sort_by_key(|inspected| std::cmp::Reverse(*inspected));
+        inspections[0] * inspections[1]
+    }
+
+    /// Plays one round, failing with the offending monkey and round if a worry level
+    /// does not fit in `u64`.
+    fn play_round(&mut self, after_inspect: impl Fn(u64) -> u64) -> Result<(), Overflow> {
+        self.rounds_played += 1;
+        let inspections_before = self.inspections();
         let mut current_monkey = 0;
         loop {
-            if let Some(mut item) = self.0[current_monkey].items.pop_front() {
-                self.0[current_monkey].inspected_items += 1;
-                item = (self.0[current_monkey].operation)(item);
-                item = after_inspect(item);
-                if item % self.0[current_monkey].test == 0 {
-                    let new_owner = self.0[current_monkey].when_true;
-                    self.0[new_owner].items.push_back(item);
+            if let Some(mut item) = self.monkeys[current_monkey].items.pop_front() {
+                self.monkeys[current_monkey].inspected_items += 1;
+                item.worry_level = self.monkeys[current_monkey]
+                    .operation
+                    .apply(item.worry_level)
+                    .ok_or(Overflow {
+                        monkey: current_monkey,
+                        round: self.rounds_played,
+                        item: item.worry_level,
+                    })?;
+                item.worry_level = after_inspect(item.worry_level);
+                let new_owner = if item.worry_level % self.monkeys[current_monkey].test == 0 {
+                    self.monkeys[current_monkey].when_true
                 } else {
-                    let new_owner = self.0[current_monkey].when_false;
-                    self.0[new_owner].items.push_back(item);
+                    self.monkeys[current_monkey].when_false
+                };
+                self.monkeys[new_owner].items.push_back(item);
+                if let Some(trace) = self.trace.as_mut() {
+                    trace.hand_over(item.id, self.rounds_played, new_owner);
                 }
             } else {
                 current_monkey += 1;
-                if current_monkey == self.0.len() {
+                if current_monkey == self.monkeys.len() {
                     break;
                 }
             }
         }
+
+        if let Some(trace) = self.trace.as_mut() {
+            let inspections = self.monkeys.iter().zip(inspections_before);
+            trace.end_round(
+                inspections
+                    .map(|(monkey, before)| monkey.inspected_items - before)
+                    .collect(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Plays `rounds` rounds without any worry relief, once with exact worry levels and once
+/// with levels kept modulo the common modulus, checking inspection counts stay identical.
+fn validate_modulus(monkeys: &Monkeys, rounds: usize) -> Result<bool, Overflow> {
+    let modulus = monkeys.common_modulus();
+    let mut exact = monkeys.clone();
+    let mut reduced = monkeys.clone();
+    for _ in 0..rounds {
+        exact.play_round(|worry_level| worry_level)?;
+        reduced.play_round(|worry_level| worry_level % modulus)?;
+        if exact.inspections() != reduced.inspections() {
+            return Ok(false);
+        }
     }
+
+    Ok(true)
 }
 
 fn part_1(mut monkeys: Monkeys) -> u64 {
     for _ in 0..20 {
-        monkeys.play_round(|worry_level| worry_level / 3);
+        monkeys.play_round(|worry_level| worry_level / 3).unwrap();
     }
 
-    monkeys
-        .0
-        .sort_by(|m1, m2| m2.inspected_items.cmp(&m1.inspected_items));
-    monkeys.0[0].inspected_items * monkeys.0[1].inspected_items
+    monkeys.monkey_business()
 }
 
 fn part_2(mut monkeys: Monkeys) -> u64 {
+    let modulus = monkeys.common_modulus();
     for _ in 0..10000 {
-        monkeys.play_round(|worry_level| worry_level % (2 * 17 * 19 * 3 * 5 * 13 * 7 * 11))
+        monkeys
+            .play_round(|worry_level| worry_level % modulus)
+            .unwrap();
     }
 
-    monkeys
-        .0
-        .sort_by(|m1, m2| m2.inspected_items.cmp(&m1.inspected_items));
-    monkeys.0[0].inspected_items * monkeys.0[1].inspected_items
+    monkeys.monkey_business()
 }
 
 fn main() {
     let monkeys = vec![
-        Monkey::new(VecDeque::from([83, 62, 93]), |old| old * 17, 2, 1, 6),
-        Monkey::new(VecDeque::from([90, 55]), |old| old + 1, 17, 6, 3),
+        Monkey::new(
+            VecDeque::from([83, 62, 93]),
+            Operation::Multiply(17),
+            2,
+            1,
+            6,
+        ),
+        Monkey::new(VecDeque::from([90, 55]), Operation::Add(1), 17, 6, 3),
         Monkey::new(
             VecDeque::from([91, 78, 80, 97, 79, 88]),
-            |old| old + 3,
+            Operation::Add(3),
             19,
             7,
             5,
         ),
-        Monkey::new(VecDeque::from([64, 80, 83, 89, 59]), |old| old + 5, 3, 7, 2),
-        Monkey::new(VecDeque::from([98, 92, 99, 51]), |old| old * old, 5, 0, 1),
+        Monkey::new(
+            VecDeque::from([64, 80, 83, 89, 59]),
+            Operation::Add(5),
+            3,
+            7,
+            2,
+        ),
+        Monkey::new(VecDeque::from([98, 92, 99, 51]), Operation::Square, 5, 0, 1),
         Monkey::new(
             VecDeque::from([68, 57, 95, 85, 98, 75, 98, 75]),
-            |old| old + 2,
+            Operation::Add(2),
             13,
             4,
             0,
         ),
-        Monkey::new(VecDeque::from([74]), |old| old + 4, 7, 3, 2),
+        Monkey::new(VecDeque::from([74]), Operation::Add(4), 7, 3, 2),
         Monkey::new(
             VecDeque::from([68, 64, 60, 68, 87, 80, 82]),
-            |old| old * 19,
+            Operation::Multiply(19),
             11,
             4,
             5,
         ),
     ];
 
-    let monkeys = Monkeys(monkeys);
+    let monkeys = Monkeys::new(monkeys);
 
     assert_eq!(part_1(monkeys.clone()), 112815);
-    assert_eq!(part_2(monkeys), 25738411485);
+    assert_eq!(part_2(monkeys.clone()), 25738411485);
+
+    assert_eq!(validate_modulus(&monkeys, 7), Ok(true));
+    assert_eq!(
+        validate_modulus(&monkeys, 8).unwrap_err().to_string(),
+        "monkey 4 overflowed inspecting item 792486344728 in round 8"
+    );
+
+    let mut traced = monkeys.clone().with_trace();
+    for _ in 0..20 {
+        traced.play_round(|worry_level| worry_level / 3).unwrap();
+    }
+    let trace = traced.trace.as_ref().unwrap();
+    assert_eq!(trace.monkey_business(20), 112815);
+    assert_eq!(trace.monkey_business(20), traced.monkey_business());
+
+    if let Some(dir) = env::args().nth(1) {
+        let rounds = env::args().nth(2).map_or(10000, |r| r.parse().unwrap());
+        let modulus = monkeys.common_modulus();
+        let mut traced = monkeys.with_trace();
+        for _ in 0..rounds {
+            traced
+                .play_round(|worry_level| worry_level % modulus)
+                .unwrap();
+        }
+        let trace = traced.trace.unwrap();
+        let dir = Path::new(&dir);
+        fs::write(dir.join("inspections.csv"), trace.inspections_csv()).unwrap();
+        fs::write(dir.join("item_paths.csv"), trace.item_paths_csv()).unwrap();
+        fs::write(dir.join("trace.json"), trace.to_json()).unwrap();
+    }
 }
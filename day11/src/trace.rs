@@ -0,0 +1,82 @@
+#[derive(Clone, Default)]
+pub struct Trace {
+    /// Inspections done by every monkey, one entry per round.
+    inspections: Vec<Vec<u64>>,
+    /// For every item, the rounds in which it changed hands and the monkey receiving it.
+    item_paths: Vec<Vec<(usize, usize)>>,
+}
+
+impl Trace {
+    pub fn hand_over(&mut self, item: usize, round: usize, monkey: usize) {
+        if self.item_paths.len() <= item {
+            self.item_paths.resize(item + 1, vec![]);
+        }
+        self.item_paths[item].push((round, monkey));
+    }
+
+    pub fn end_round(&mut self, inspections: Vec<u64>) {
+        self.inspections.push(inspections);
+    }
+
+    /// Product of the two highest inspection counts after the first `rounds` rounds.
+    pub fn monkey_business(&self, rounds: usize) -> u64 {
+        let mut totals = vec![0; self.inspections.first().map_or(0, Vec::len)];
+        for round in self.inspections.iter().take(rounds) {
+            for (total, inspected) in totals.iter_mut().zip(round) {
+                *total += inspected;
+            }
+        }
+        totals.sort_by_key(|total| std::cmp::Reverse(*total));
+        totals[0] * totals[1]
+    }
+
+    pub fn inspections_csv(&self) -> String {
+        let monkeys = self.inspections.first().map_or(0, Vec::len);
+        let mut csv = String::from("round");
+        for monkey in 0..monkeys {
+            csv += &format!(",monkey_{monkey}");
+        }
+        csv.push('\n');
+        for (round, inspections) in self.inspections.iter().enumerate() {
+            csv += &(round + 1).to_string();
+            for inspected in inspections {
+                csv += &format!(",{inspected}");
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn item_paths_csv(&self) -> String {
+        let mut csv = String::from("item,round,monkey\n");
+        for (item, path) in self.item_paths.iter().enumerate() {
+            for (round, monkey) in path {
+                csv += &format!("{item},{round},{monkey}\n");
+            }
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let inspections = self
+            .inspections
+            .iter()
+            .map(|round| format!("{round:?}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let item_paths = self
+            .item_paths
+            .iter()
+            .map(|path| {
+                let hops = path
+                    .iter()
+                    .map(|(round, monkey)| format!("{{\"round\":{round},\"monkey\":{monkey}}}"))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{hops}]")
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{{\"inspections\":[{inspections}],\"item_paths\":[{item_paths}]}}\n")
+    }
+}
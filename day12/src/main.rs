@@ -1,47 +1,60 @@
-use std::collections::{HashMap, VecDeque};
+mod rules;
 
-fn part_1(map: &[Vec<u8>]) -> i32 {
+use grid::{Grid, Position};
+use rules::ClimbRules;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    env, fs,
+};
+
+const EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+fn part_1(map: &Grid<u8>) -> i32 {
     let start = find_on_map(map, b'S')[0];
     let end = find_on_map(map, b'E')[0];
-    traverse(map, start, end).unwrap()
+    traverse(map, start, end, &ClimbRules::default())
+        .unwrap()
+        .len() as i32
+        - 1
 }
 
-fn part_2(map: &[Vec<u8>]) -> i32 {
-    let mut starting_positions = find_on_map(map, b'S');
-    starting_positions.append(&mut find_on_map(map, b'a'));
+fn part_2(map: &Grid<u8>) -> i32 {
     let end = find_on_map(map, b'E')[0];
-
-    starting_positions
-        .into_iter()
-        .filter_map(|start| traverse(map, start, end))
-        .min()
-        .unwrap()
+    nearest_with_elevation(map, &distances_to(map, end, &ClimbRules::default()), b'a').unwrap()
 }
 
-fn find_on_map(map: &[Vec<u8>], needle: u8) -> Vec<(usize, usize)> {
-    let mut result = vec![];
-    for (y, row) in map.iter().enumerate() {
-        for (x, c) in row.iter().enumerate() {
-            if c == &needle {
-                result.push((x, y));
-            }
-        }
+fn elevation(square: u8) -> u8 {
+    match square {
+        b'S' => b'a',
+        b'E' => b'z',
+        _ => square,
     }
+}
 
-    result
+fn find_on_map(map: &Grid<u8>, needle: u8) -> Vec<Position> {
+    map.find_all(|c| c == &needle).collect()
 }
 
 struct State {
-    position: (usize, usize),
+    position: Position,
     steps_taken: i32,
 }
 
-fn traverse(map: &[Vec<u8>], start: (usize, usize), end: (usize, usize)) -> Option<i32> {
-    let height = map.len();
-    let width = map[0].len();
-
-    let mut visited: HashMap<(usize, usize), i32> = HashMap::new();
+/// Shortest route from `start` to `end`, both included.
+fn traverse(
+    map: &Grid<u8>,
+    start: Position,
+    end: Position,
+    rules: &ClimbRules,
+) -> Option<Vec<Position>> {
+    let mut visited: HashMap<Position, i32> = HashMap::new();
     visited.insert(start, 0);
+    let mut predecessors: HashMap<Position, Position> = HashMap::new();
 
     let mut to_visit = VecDeque::new();
     to_visit.push_back(State {
@@ -54,16 +67,14 @@ fn traverse(map: &[Vec<u8>], start: (usize, usize), end: (usize, usize)) -> Opti
             break;
         }
 
-        for neighbour in get_neighbours(state.position, height, width) {
-            if can_climb(
-                map[state.position.1][state.position.0],
-                map[neighbour.1][neighbour.0],
-            ) {
+        for neighbour in rules.neighbours(state.position, map.width(), map.height()) {
+            if can_climb(map, state.position, neighbour, rules) {
                 if *visited.get(&neighbour).unwrap_or(&i32::MAX) <= state.steps_taken + 1 {
                     continue;
                 }
 
                 visited.insert(neighbour, state.steps_taken + 1);
+                predecessors.insert(neighbour, state.position);
 
                 to_visit.push_back(State {
                     position: neighbour,
@@ -73,48 +84,188 @@ fn traverse(map: &[Vec<u8>], start: (usize, usize), end: (usize, usize)) -> Opti
         }
     }
 
-    visited.get(&end).copied()
+    visited.get(&end)?;
+    let mut route = vec![end];
+    while let Some(previous) = predecessors.get(route.last().unwrap()) {
+        route.push(*previous);
+    }
+    route.reverse();
+
+    Some(route)
 }
 
-fn can_climb(from: u8, to: u8) -> bool {
-    let from = if from == b'S' { b'a' } else { from } as i8;
-    let to = if to == b'E' { b'z' } else { to } as i8;
+/// Draws the heightmap with every step of `route` replaced by an arrow towards the next one.
+fn render_route(map: &Grid<u8>, route: &[Position]) -> String {
+    let mut canvas = map.map(|square| *square as char);
+    for step in route.windows(2) {
+        let (from, to) = (step[0], step[1]);
+        canvas[from] = match (
+            to.x as isize - from.x as isize,
+            to.y as isize - from.y as isize,
+        ) {
+            (0, -1) => '^',
+            (0, 1) => 'v',
+            (-1, 0) => '<',
+            (1, 0) => '>',
+            (-1, -1) | (1, 1) => '\\',
+            (1, -1) | (-1, 1) => '/',
+            offset => panic!("route steps by {offset:?}"),
+        };
+    }
 
-    to - from <= 1
+    (0..canvas.height())
+        .map(|y| canvas.row(y).collect::<String>() + "\n")
+        .collect()
 }
 
-fn get_neighbours(
-    position: (usize, usize),
-    height: usize,
-    width: usize,
-) -> impl Iterator<Item = (usize, usize)> {
-    let directions = [
-        (0, -1), // up
-        (1, 0),  // left
-        (0, 1),  // down
-        (-1, 0), //right
-    ];
-
-    let in_bounds = move |position: (i32, i32)| {
-        position.0 >= 0
-            && position.0 < width as i32
-            && position.1 >= 0
-            && position.1 < height as i32
-    };
+/// Shortest number of steps from every square to `end`, `None` where `end` is unreachable.
+fn distances_to(map: &Grid<u8>, end: Position, rules: &ClimbRules) -> Grid<Option<i32>> {
+    let mut distances = Grid::filled(map.width(), map.height(), None);
+    distances[end] = Some(0);
+
+    let mut to_visit = VecDeque::new();
+    to_visit.push_back(State {
+        position: end,
+        steps_taken: 0,
+    });
+
+    while let Some(state) = to_visit.pop_front() {
+        for neighbour in rules.neighbours(state.position, map.width(), map.height()) {
+            if distances[neighbour].is_none() && can_climb(map, neighbour, state.position, rules) {
+                distances[neighbour] = Some(state.steps_taken + 1);
+                to_visit.push_back(State {
+                    position: neighbour,
+                    steps_taken: state.steps_taken + 1,
+                });
+            }
+        }
+    }
 
-    directions.into_iter().filter_map(move |dir| {
-        let new_pos = (position.0 as i32 + dir.0, position.1 as i32 + dir.1);
-        in_bounds(new_pos).then_some((new_pos.0 as usize, new_pos.1 as usize))
-    })
+    distances
+}
+
+fn nearest_with_elevation(
+    map: &Grid<u8>,
+    distances: &Grid<Option<i32>>,
+    needle: u8,
+) -> Option<i32> {
+    map.find_all(|square| elevation(*square) == needle)
+        .filter_map(|position| distances[position])
+        .min()
+}
+
+fn can_climb(map: &Grid<u8>, from: Position, to: Position, rules: &ClimbRules) -> bool {
+    rules.can_climb(elevation(map[from]), elevation(map[to]))
+}
+
+/// Cheapest route from `start` to `end` under `rules` and its total cost.
+fn a_star(
+    map: &Grid<u8>,
+    start: Position,
+    end: Position,
+    rules: &ClimbRules,
+) -> Option<(u32, Vec<Position>)> {
+    let mut costs: HashMap<Position, u32> = HashMap::new();
+    costs.insert(start, 0);
+    let mut predecessors: HashMap<Position, Position> = HashMap::new();
+
+    let mut to_visit = BinaryHeap::new();
+    to_visit.push(Reverse((rules.heuristic(start, end), 0, start)));
+
+    while let Some(Reverse((_, cost, position))) = to_visit.pop() {
+        if position == end {
+            let mut route = vec![end];
+            while let Some(previous) = predecessors.get(route.last().unwrap()) {
+                route.push(*previous);
+            }
+            route.reverse();
+            return Some((cost, route));
+        }
+        if cost > costs[&position] {
+            continue;
+        }
+
+        for neighbour in rules.neighbours(position, map.width(), map.height()) {
+            if !can_climb(map, position, neighbour, rules) {
+                continue;
+            }
+            let new_cost = cost + rules.cost(elevation(map[position]), elevation(map[neighbour]));
+            if *costs.get(&neighbour).unwrap_or(&u32::MAX) <= new_cost {
+                continue;
+            }
+
+            costs.insert(neighbour, new_cost);
+            predecessors.insert(neighbour, position);
+            to_visit.push(Reverse((
+                new_cost + rules.heuristic(neighbour, end),
+                new_cost,
+                neighbour,
+            )));
+        }
+    }
+
+    None
 }
 
 fn main() {
+    let example = Grid::parse(EXAMPLE, |c| c as u8);
+    assert_eq!(part_1(&example), 31);
+    assert_eq!(part_2(&example), 29);
+    let example_start = find_on_map(&example, b'S')[0];
+    let example_end = find_on_map(&example, b'E')[0];
+    let example_route =
+        traverse(&example, example_start, example_end, &ClimbRules::default()).unwrap();
+    assert_eq!(
+        render_route(&example, &example_route),
+        ">>vv<<<<\nabvvv<<^\nacvv>E^^\nacv>>>^^\nab>>>>>^\n"
+    );
+
     let input = std::fs::read_to_string("input").unwrap();
-    let map = input
-        .lines()
-        .map(|l| l.chars().map(|c| c as u8).collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+    let map = Grid::parse(&input, |c| c as u8);
 
     assert_eq!(part_1(&map), 490);
     assert_eq!(part_2(&map), 488);
+
+    let start = find_on_map(&map, b'S')[0];
+    let end = find_on_map(&map, b'E')[0];
+    let rules = ClimbRules::default();
+    assert_eq!(distances_to(&map, end, &rules)[start], Some(490));
+    let shortest_route = traverse(&map, start, end, &rules).unwrap();
+    assert_eq!(shortest_route.len() - 1, 490);
+    assert_eq!(
+        (shortest_route[0], shortest_route[shortest_route.len() - 1]),
+        (start, end)
+    );
+    assert!(shortest_route
+        .windows(2)
+        .all(|step| map.neighbours4(step[0]).any(|p| p == step[1])));
+    assert_eq!(a_star(&map, start, end, &rules).unwrap().0, 490);
+
+    let diagonal = ClimbRules {
+        diagonal: true,
+        ..Default::default()
+    };
+    let (cost, route) = a_star(&map, start, end, &diagonal).unwrap();
+    assert_eq!(cost as usize, route.len() - 1);
+    assert_eq!(
+        traverse(&map, start, end, &diagonal).unwrap().len(),
+        route.len()
+    );
+
+    let weighted = ClimbRules {
+        max_descent: 2,
+        ascent_cost: 3,
+        ..Default::default()
+    };
+    let (cost, route) = a_star(&map, start, end, &weighted).unwrap();
+    assert!(cost as usize >= route.len() - 1 + 3 * 25);
+
+    if let Some(path) = env::args().nth(1) {
+        let rendered = render_route(&map, &shortest_route);
+        if path == "-" {
+            print!("{rendered}");
+        } else {
+            fs::write(path, rendered).unwrap();
+        }
+    }
 }
@@ -0,0 +1,67 @@
+use grid::{Position, OFFSETS_4, OFFSETS_8};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClimbRules {
+    pub max_ascent: u8,
+    pub max_descent: u8,
+    pub diagonal: bool,
+    /// Cost of every step, on top of the elevation change costs.
+    pub step_cost: u32,
+    /// Cost per level climbed up.
+    pub ascent_cost: u32,
+    /// Cost per level climbed down.
+    pub descent_cost: u32,
+}
+
+impl Default for ClimbRules {
+    /// Puzzle rules: at most one level up, any amount down, every step costs one.
+    fn default() -> Self {
+        Self {
+            max_ascent: 1,
+            max_descent: u8::MAX,
+            diagonal: false,
+            step_cost: 1,
+            ascent_cost: 0,
+            descent_cost: 0,
+        }
+    }
+}
+
+impl ClimbRules {
+    pub fn can_climb(&self, from: u8, to: u8) -> bool {
+        if to >= from {
+            to - from <= self.max_ascent
+        } else {
+            from - to <= self.max_descent
+        }
+    }
+
+    pub fn cost(&self, from: u8, to: u8) -> u32 {
+        self.step_cost
+            + to.saturating_sub(from) as u32 * self.ascent_cost
+            + from.saturating_sub(to) as u32 * self.descent_cost
+    }
+
+    /// Lower bound on the cost of getting from `from` to `to`, ignoring elevation.
+    pub fn heuristic(&self, from: Position, to: Position) -> u32 {
+        let (dx, dy) = (from.x.abs_diff(to.x), from.y.abs_diff(to.y));
+        let steps = if self.diagonal { dx.max(dy) } else { dx + dy };
+        steps as u32 * self.step_cost
+    }
+
+    pub fn neighbours(
+        &self,
+        position: Position,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = Position> {
+        let offsets: &[(isize, isize)] = if self.diagonal {
+            &OFFSETS_8
+        } else {
+            &OFFSETS_4
+        };
+        offsets
+            .iter()
+            .filter_map(move |offset| position.offset(*offset, width, height))
+    }
+}
@@ -3,78 +3,116 @@ use std::{
     collections::HashMap,
 };
 
+const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
 #[derive(Debug, Clone, Copy)]
 enum Material {
-    Air,
     Rock,
     Sand,
 }
 
-fn part_1(mut cave: HashMap<(u32, u32), Material>, max_y: u32) -> u32 {
-    for i in 0.. {
-        let mut pos = (500, 0);
+#[derive(Debug, Clone, Copy)]
+enum Floor {
+    /// No floor: a source whose grain falls below the lowest rock stops pouring.
+    None,
+    /// No floor: the simulation ends as soon as any grain falls below the lowest rock.
+    Abyss,
+    /// Infinite solid floor placed this many rows below the lowest rock.
+    Solid(i32),
+}
 
-        loop {
-            if pos.1 == max_y {
-                return i;
-            }
-            match cave.get(&(pos.0, pos.1 + 1)).unwrap_or(&Material::Air) {
-                Material::Air => pos.1 += 1,
-                Material::Rock | Material::Sand => {
-                    if cave.get(&(pos.0 - 1, pos.1 + 1)).is_none() {
-                        pos.0 -= 1;
-                        pos.1 += 1;
-                    } else if cave.get(&(pos.0 + 1, pos.1 + 1)).is_none() {
-                        pos.0 += 1;
-                        pos.1 += 1;
-                    } else {
-                        break;
-                    }
-                }
-            }
+#[derive(Debug, Clone)]
+struct CaveConfig {
+    sources: Vec<(i32, i32)>,
+    floor: Floor,
+}
+
+impl Default for CaveConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![(500, 0)],
+            floor: Floor::Abyss,
         }
-        cave.insert(pos, Material::Sand);
     }
+}
+
+fn part_1(cave: HashMap<(i32, i32), Material>) -> u32 {
+    simulate(cave, &CaveConfig::default())
+}
 
-    unreachable!()
+fn part_2(cave: HashMap<(i32, i32), Material>) -> u32 {
+    simulate(
+        cave,
+        &CaveConfig {
+            floor: Floor::Solid(2),
+            ..Default::default()
+        },
+    )
 }
 
-fn part_2(mut cave: HashMap<(u32, u32), Material>, max_y: u32) -> u32 {
-    for i in 0.. {
-        let mut pos = (500, 0);
-        loop {
-            if cave.get(&(500, 0)).is_some() {
-                return i;
+fn simulate(mut cave: HashMap<(i32, i32), Material>, config: &CaveConfig) -> u32 {
+    let max_y = cave.keys().map(|(_, y)| *y).max().unwrap_or(0);
+    let mut active_sources = config.sources.clone();
+    let mut settled = 0;
+
+    while !active_sources.is_empty() {
+        let mut abyss_reached = false;
+        active_sources.retain(|source| {
+            if abyss_reached || cave.contains_key(source) {
+                return false;
             }
-            match cave.get(&(pos.0, pos.1 + 1)).unwrap_or(&Material::Air) {
-                Material::Air => {
-                    if pos.1 + 1 > max_y + 1 {
-                        break;
-                    } else {
-                        pos.1 += 1;
-                    }
+            match drop_grain(&cave, *source, max_y, config.floor) {
+                Some(pos) => {
+                    cave.insert(pos, Material::Sand);
+                    settled += 1;
+                    true
                 }
-                Material::Rock | Material::Sand => {
-                    if cave.get(&(pos.0 - 1, pos.1 + 1)).is_none() {
-                        pos.0 -= 1;
-                        pos.1 += 1;
-                    } else if cave.get(&(pos.0 + 1, pos.1 + 1)).is_none() {
-                        pos.0 += 1;
-                        pos.1 += 1;
-                    } else {
-                        break;
-                    }
+                None => {
+                    abyss_reached = matches!(config.floor, Floor::Abyss);
+                    false
                 }
             }
+        });
+        if abyss_reached {
+            break;
         }
-        cave.insert(pos, Material::Sand);
     }
-    unreachable!()
+
+    settled
 }
 
-fn main() {
-    let input = std::fs::read_to_string("input").unwrap();
+fn drop_grain(
+    cave: &HashMap<(i32, i32), Material>,
+    source: (i32, i32),
+    max_y: i32,
+    floor: Floor,
+) -> Option<(i32, i32)> {
+    let floor_y = match floor {
+        Floor::Solid(offset) => Some(max_y + offset),
+        Floor::None | Floor::Abyss => None,
+    };
 
+    let mut pos = source;
+    loop {
+        match floor_y {
+            Some(floor_y) if pos.1 + 1 >= floor_y => return Some(pos),
+            None if pos.1 >= max_y => return None,
+            _ => (),
+        }
+
+        let next = [(0, 1), (-1, 1), (1, 1)]
+            .into_iter()
+            .map(|(dx, dy)| (pos.0 + dx, pos.1 + dy))
+            .find(|next| !cave.contains_key(next));
+        match next {
+            Some(next) => pos = next,
+            None => return Some(pos),
+        }
+    }
+}
+
+fn parse_cave(input: &str) -> HashMap<(i32, i32), Material> {
     let mut cave = HashMap::new();
     input.lines().for_each(|l| {
         let mut previous_point = None;
@@ -82,8 +120,8 @@ fn main() {
         points.for_each(|s| {
             let mut coords = s.split(',');
             let (x, y) = (
-                coords.next().unwrap().parse::<u32>().unwrap(),
-                coords.next().unwrap().parse::<u32>().unwrap(),
+                coords.next().unwrap().parse::<i32>().unwrap(),
+                coords.next().unwrap().parse::<i32>().unwrap(),
             );
 
             if let Some((prev_x, prev_y)) = previous_point {
@@ -102,8 +140,22 @@ fn main() {
         });
     });
 
-    let max_y = cave.keys().max_by(|lhs, rhs| lhs.1.cmp(&rhs.1)).unwrap().1;
+    cave
+}
+
+fn main() {
+    let example = parse_cave(EXAMPLE);
+    assert_eq!(part_1(example.clone()), 24);
+    assert_eq!(part_2(example.clone()), 93);
+    let two_sources = CaveConfig {
+        sources: vec![(500, 0), (520, 0)],
+        floor: Floor::None,
+    };
+    assert_eq!(simulate(example, &two_sources), 24);
+
+    let input = std::fs::read_to_string("input").unwrap();
+    let cave = parse_cave(&input);
 
-    assert_eq!(part_1(cave.clone(), max_y), 592);
-    assert_eq!(part_2(cave, max_y), 30367);
+    assert_eq!(part_1(cave.clone()), 592);
+    assert_eq!(part_2(cave), 30367);
 }
@@ -1,6 +1,31 @@
-use std::collections::{HashSet, VecDeque};
+mod mesh;
+mod report;
+mod voxel;
 
-const SIDES: [(i32, i32, i32); 6] = [
+use mesh::Face;
+use report::Report;
+use std::{
+    collections::{HashSet, VecDeque},
+    env, fs,
+    path::Path,
+};
+use voxel::{Cube, VoxelGrid};
+
+const EXAMPLE: &str = "2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
+pub const SIDES: [(i32, i32, i32); 6] = [
     (1, 0, 0),
     (-1, 0, 0),
     (0, 1, 0),
@@ -9,7 +34,7 @@ const SIDES: [(i32, i32, i32); 6] = [
     (0, 0, -1),
 ];
 
-fn count_exposed(coords: &HashSet<(i32, i32, i32)>) -> usize {
+fn count_exposed(coords: &HashSet<Cube>) -> usize {
     coords
         .iter()
         .map(|c| {
@@ -24,51 +49,103 @@ fn count_exposed(coords: &HashSet<(i32, i32, i32)>) -> usize {
         .sum()
 }
 
-fn remove_air_pockets(coords: &HashSet<(i32, i32, i32)>) -> HashSet<(i32, i32, i32)> {
-    let (max_x, max_y, max_z) = coords.iter().fold(
-        (i32::MIN, i32::MIN, i32::MIN),
-        |(max_x, max_y, max_z), (cx, cy, cz)| {
-            (
-                std::cmp::max(max_x, *cx),
-                std::cmp::max(max_y, *cy),
-                std::cmp::max(max_z, *cz),
-            )
-        },
-    );
+fn exposed_faces(coords: &HashSet<Cube>) -> Vec<Face> {
+    coords
+        .iter()
+        .flat_map(|c| {
+            SIDES
+                .iter()
+                .filter(|s| !coords.contains(&(c.0 + s.0, c.1 + s.1, c.2 + s.2)))
+                .map(|s| Face {
+                    cube: *c,
+                    normal: *s,
+                })
+        })
+        .collect()
+}
+
+pub struct Flood {
+    pub lava: VoxelGrid,
+    pub water: VoxelGrid,
+    pub exterior_faces: Vec<Face>,
+}
+
+/// Floods the padded bounding box from its corner, collecting every lava face the water
+/// touches.
+fn flood_exterior(coords: &HashSet<Cube>) -> Flood {
+    let mut lava = VoxelGrid::padded_around(coords);
+    coords.iter().for_each(|c| {
+        lava.insert(*c);
+    });
+    let mut water = VoxelGrid::empty_like(&lava);
 
     let mut to_check = VecDeque::new();
-    to_check.push_back((0, 0, 0));
-    let mut water = HashSet::new();
+    to_check.push_back(lava.min());
+    water.insert(lava.min());
+    let mut exterior_faces = vec![];
 
     while let Some(c) = to_check.pop_front() {
-        if c.0 < 0 || c.0 > max_x || c.1 < 0 || c.1 > max_y || c.2 < 0 || c.2 > max_z {
-            continue;
-        }
-        if coords.contains(&c) {
-            continue;
-        } else if water.insert(c) {
-            SIDES.iter().for_each(|s| {
-                let new_coords = (c.0 + s.0, c.1 + s.1, c.2 + s.2);
+        SIDES.iter().for_each(|s| {
+            let new_coords = (c.0 + s.0, c.1 + s.1, c.2 + s.2);
+            if !lava.in_bounds(new_coords) {
+                return;
+            }
+            if lava.contains(new_coords) {
+                exterior_faces.push(Face {
+                    cube: new_coords,
+                    normal: (-s.0, -s.1, -s.2),
+                });
+            } else if water.insert(new_coords) {
                 to_check.push_back(new_coords);
-            });
-        }
+            }
+        });
     }
 
-    let mut entire_space = HashSet::new();
-    for x in 0..=max_x {
-        for y in 0..=max_y {
-            for z in 0..=max_z {
-                entire_space.insert((x, y, z));
+    Flood {
+        lava,
+        water,
+        exterior_faces,
+    }
+}
+
+fn count_exterior(coords: &HashSet<Cube>) -> usize {
+    flood_exterior(coords).exterior_faces.len()
+}
+
+/// Surfaces of the enclosed air pockets, facing out of the pockets into the lava.
+fn pocket_faces(coords: &HashSet<Cube>, flood: &Flood) -> Vec<Face> {
+    exposed_faces(coords)
+        .into_iter()
+        .map(|face| {
+            let n = face.normal;
+            let (c, n) = (face.cube, (-n.0, -n.1, -n.2));
+            Face {
+                cube: (c.0 - n.0, c.1 - n.1, c.2 - n.2),
+                normal: n,
             }
-        }
+        })
+        .filter(|face| !flood.water.contains(face.cube) && !flood.lava.contains(face.cube))
+        .collect()
+}
+
+fn export_meshes(coords: &HashSet<Cube>, dir: &Path, with_pockets: bool) {
+    let flood = flood_exterior(coords);
+    let mut meshes = vec![
+        ("droplet", exposed_faces(coords)),
+        ("droplet_exterior", flood.exterior_faces.clone()),
+    ];
+    if with_pockets {
+        meshes.push(("air_pockets", pocket_faces(coords, &flood)));
     }
 
-    entire_space.difference(&water).copied().collect()
+    for (name, faces) in meshes {
+        fs::write(dir.join(format!("{name}.stl")), mesh::to_stl(name, &faces)).unwrap();
+        fs::write(dir.join(format!("{name}.obj")), mesh::to_obj(name, &faces)).unwrap();
+    }
 }
 
-fn main() {
-    let input = std::fs::read_to_string("input").unwrap();
-    let coords = input
+fn parse(input: &str) -> HashSet<Cube> {
+    input
         .lines()
         .map(|l| {
             let mut coords = l.split(',').map(|coord| coord.parse::<i32>().unwrap());
@@ -78,12 +155,62 @@ fn main() {
                 coords.next().unwrap(),
             )
         })
+        .collect()
+}
+
+fn main() {
+    let example = parse(EXAMPLE);
+    assert_eq!(count_exposed(&example), 64);
+    assert_eq!(count_exterior(&example), 58);
+    assert_eq!(exposed_faces(&example).len(), 64);
+    let pocket = pocket_faces(&example, &flood_exterior(&example));
+    assert_eq!(pocket.len(), 6);
+    assert!(pocket.iter().all(|face| face.cube == (2, 2, 5)));
+    assert_eq!(
+        mesh::to_stl("droplet", &pocket)
+            .matches("facet normal")
+            .count(),
+        12
+    );
+
+    let report = Report::new(&example, &flood_exterior(&example), 64);
+    assert_eq!(report.lava_components, 6);
+    assert_eq!(report.air_pockets.len(), 1);
+    assert_eq!(report.air_pockets[0].volume, 1);
+    assert_eq!(report.air_pockets[0].min, (2, 2, 5));
+    assert_eq!(report.air_pockets[0].surface, 6);
+
+    let shifted = example
+        .iter()
+        .map(|c| (c.0 - 3, c.1 - 2, c.2 - 10))
         .collect::<HashSet<_>>();
+    assert_eq!(count_exterior(&shifted), 58);
+
+    let nothing = HashSet::new();
+    assert_eq!(count_exterior(&nothing), 0);
+    assert!(Report::new(&nothing, &flood_exterior(&nothing), 0)
+        .air_pockets
+        .is_empty());
+
+    let input = std::fs::read_to_string("input").unwrap();
+    let coords = parse(&input);
 
     let part_1 = count_exposed(&coords);
     assert_eq!(part_1, 4456);
 
-    let coords_no_air_pockets = remove_air_pockets(&coords);
-    let part_2 = count_exposed(&coords_no_air_pockets);
+    let part_2 = count_exterior(&coords);
     assert_eq!(part_2, 2510);
+
+    let report = Report::new(&coords, &flood_exterior(&coords), part_1);
+    let pocket_surface = report.air_pockets.iter().map(|p| p.surface).sum::<usize>();
+    assert_eq!(report.exterior_surface + pocket_surface, part_1);
+
+    match env::args().nth(1).as_deref() {
+        Some("report") => print!("{report}"),
+        Some(dir) => {
+            let with_pockets = env::args().any(|arg| arg == "--pockets");
+            export_meshes(&coords, Path::new(dir), with_pockets);
+        }
+        None => (),
+    }
 }
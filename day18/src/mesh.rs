@@ -0,0 +1,69 @@
+use crate::voxel::Cube;
+
+/// Unit square on the side of `cube` that `normal` points out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Face {
+    pub cube: Cube,
+    pub normal: Cube,
+}
+
+impl Face {
+    /// Corners in counter-clockwise order when looking against the normal.
+    fn corners(&self) -> [[i32; 3]; 4] {
+        let normal = [self.normal.0, self.normal.1, self.normal.2];
+        let axis = normal.iter().position(|n| *n != 0).unwrap();
+        let (mut u, mut v) = ((axis + 1) % 3, (axis + 2) % 3);
+        if normal[axis] < 0 {
+            std::mem::swap(&mut u, &mut v);
+        }
+
+        let mut base = [self.cube.0, self.cube.1, self.cube.2];
+        if normal[axis] > 0 {
+            base[axis] += 1;
+        }
+        let offset = |du: i32, dv: i32| {
+            let mut corner = base;
+            corner[u] += du;
+            corner[v] += dv;
+            corner
+        };
+        [offset(0, 0), offset(1, 0), offset(1, 1), offset(0, 1)]
+    }
+
+    fn triangles(&self) -> [[[i32; 3]; 3]; 2] {
+        let [a, b, c, d] = self.corners();
+        [[a, b, c], [a, c, d]]
+    }
+}
+
+pub fn to_stl(name: &str, faces: &[Face]) -> String {
+    let mut stl = format!("solid {name}\n");
+    for face in faces {
+        let n = face.normal;
+        for triangle in face.triangles() {
+            stl += &format!("  facet normal {} {} {}\n    outer loop\n", n.0, n.1, n.2);
+            for [x, y, z] in triangle {
+                stl += &format!("      vertex {x} {y} {z}\n");
+            }
+            stl += "    endloop\n  endfacet\n";
+        }
+    }
+    stl + &format!("endsolid {name}\n")
+}
+
+pub fn to_obj(name: &str, faces: &[Face]) -> String {
+    let mut obj = format!("o {name}\n");
+    for face in faces {
+        for [x, y, z] in face.corners() {
+            obj += &format!("v {x} {y} {z}\n");
+        }
+        let n = face.normal;
+        obj += &format!("vn {} {} {}\n", n.0, n.1, n.2);
+    }
+    for i in 0..faces.len() {
+        let (v, n) = (4 * i + 1, i + 1);
+        obj += &format!("f {v}//{n} {}//{n} {}//{n}\n", v + 1, v + 2);
+        obj += &format!("f {v}//{n} {}//{n} {}//{n}\n", v + 2, v + 3);
+    }
+    obj
+}
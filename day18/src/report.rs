@@ -0,0 +1,117 @@
+use crate::{voxel::Cube, Flood, SIDES};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+};
+
+pub struct AirPocket {
+    pub volume: usize,
+    pub min: Cube,
+    pub max: Cube,
+    /// Lava faces enclosing the pocket.
+    pub surface: usize,
+}
+
+pub struct Report {
+    pub lava_cubes: usize,
+    pub lava_components: usize,
+    pub total_surface: usize,
+    pub exterior_surface: usize,
+    pub air_pockets: Vec<AirPocket>,
+}
+
+impl Report {
+    pub fn new(coords: &HashSet<Cube>, flood: &Flood, total_surface: usize) -> Self {
+        let lava_components = components(coords.iter().copied(), |c| coords.contains(&c)).len();
+        let pocket_cubes = flood
+            .lava
+            .cubes()
+            .filter(|c| !flood.lava.contains(*c) && !flood.water.contains(*c));
+        let air_pockets = components(pocket_cubes, |c| {
+            !flood.lava.contains(c) && !flood.water.contains(c) && flood.lava.in_bounds(c)
+        })
+        .into_iter()
+        .map(|cubes| air_pocket(&cubes, flood))
+        .collect();
+
+        Self {
+            lava_cubes: coords.len(),
+            lava_components,
+            total_surface,
+            exterior_surface: flood.exterior_faces.len(),
+            air_pockets,
+        }
+    }
+}
+
+/// Groups `cubes` into face-connected components, walking only through cubes accepted by
+/// `belongs`.
+fn components(cubes: impl Iterator<Item = Cube>, belongs: impl Fn(Cube) -> bool) -> Vec<Vec<Cube>> {
+    let mut seen = HashSet::new();
+    let mut components = vec![];
+    for start in cubes {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut component = vec![];
+        let mut to_check = VecDeque::from([start]);
+        while let Some(c) = to_check.pop_front() {
+            component.push(c);
+            SIDES.iter().for_each(|s| {
+                let new_coords = (c.0 + s.0, c.1 + s.1, c.2 + s.2);
+                if belongs(new_coords) && seen.insert(new_coords) {
+                    to_check.push_back(new_coords);
+                }
+            });
+        }
+        components.push(component);
+    }
+
+    components
+}
+
+fn air_pocket(cubes: &[Cube], flood: &Flood) -> AirPocket {
+    let (min, max) = cubes.iter().fold((cubes[0], cubes[0]), |(min, max), c| {
+        (
+            (min.0.min(c.0), min.1.min(c.1), min.2.min(c.2)),
+            (max.0.max(c.0), max.1.max(c.1), max.2.max(c.2)),
+        )
+    });
+    let surface = cubes
+        .iter()
+        .flat_map(|c| SIDES.iter().map(move |s| (c.0 + s.0, c.1 + s.1, c.2 + s.2)))
+        .filter(|neighbour| flood.lava.contains(*neighbour))
+        .count();
+
+    AirPocket {
+        volume: cubes.len(),
+        min,
+        max,
+        surface,
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "lava cubes: {}", self.lava_cubes)?;
+        writeln!(f, "lava components: {}", self.lava_components)?;
+        writeln!(
+            f,
+            "surface area: {} total, {} exterior",
+            self.total_surface, self.exterior_surface
+        )?;
+        writeln!(f, "air pockets: {}", self.air_pockets.len())?;
+        for (i, pocket) in self.air_pockets.iter().enumerate() {
+            writeln!(
+                f,
+                "  #{}: volume {}, bounding box {:?}..={:?}, surface {}",
+                i + 1,
+                pocket.volume,
+                pocket.min,
+                pocket.max,
+                pocket.surface
+            )?;
+        }
+        Ok(())
+    }
+}
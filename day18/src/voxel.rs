@@ -0,0 +1,99 @@
+pub type Cube = (i32, i32, i32);
+
+/// Dense bitset over a box of cubes.
+#[derive(Clone)]
+pub struct VoxelGrid {
+    min: Cube,
+    size: (usize, usize, usize),
+    bits: Vec<u64>,
+}
+
+impl VoxelGrid {
+    /// Empty grid spanning the bounding box of `cubes` plus one cube of padding on every
+    /// side, so the outermost layer is always reachable air. Without any cubes that is a
+    /// single cube of air at the origin.
+    pub fn padded_around<'a>(cubes: impl IntoIterator<Item = &'a Cube>) -> Self {
+        let bounds = cubes.into_iter().fold(None, |bounds, &c| {
+            let (min, max): (Cube, Cube) = bounds.unwrap_or((c, c));
+            Some((
+                (min.0.min(c.0), min.1.min(c.1), min.2.min(c.2)),
+                (max.0.max(c.0), max.1.max(c.1), max.2.max(c.2)),
+            ))
+        });
+        let Some((min, max)) = bounds else {
+            return Self {
+                min: (0, 0, 0),
+                size: (1, 1, 1),
+                bits: vec![0; 1],
+            };
+        };
+
+        let min = (min.0 - 1, min.1 - 1, min.2 - 1);
+        let size = (
+            (max.0 - min.0 + 2) as usize,
+            (max.1 - min.1 + 2) as usize,
+            (max.2 - min.2 + 2) as usize,
+        );
+        let len = size.0 * size.1 * size.2;
+
+        Self {
+            min,
+            size,
+            bits: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    /// Same box as `other`, but with nothing set.
+    pub fn empty_like(other: &Self) -> Self {
+        Self {
+            bits: vec![0; other.bits.len()],
+            ..*other
+        }
+    }
+
+    pub fn min(&self) -> Cube {
+        self.min
+    }
+
+    fn index(&self, c: Cube) -> Option<usize> {
+        let (x, y, z) = (c.0 - self.min.0, c.1 - self.min.1, c.2 - self.min.2);
+        if x < 0 || y < 0 || z < 0 {
+            return None;
+        }
+        let (x, y, z) = (x as usize, y as usize, z as usize);
+        (x < self.size.0 && y < self.size.1 && z < self.size.2)
+            .then_some((z * self.size.1 + y) * self.size.0 + x)
+    }
+
+    /// Every cube of the box, set or not.
+    pub fn cubes(&self) -> impl Iterator<Item = Cube> + '_ {
+        (0..self.size.2).flat_map(move |z| {
+            (0..self.size.1).flat_map(move |y| {
+                (0..self.size.0).map(move |x| {
+                    (
+                        self.min.0 + x as i32,
+                        self.min.1 + y as i32,
+                        self.min.2 + z as i32,
+                    )
+                })
+            })
+        })
+    }
+
+    pub fn in_bounds(&self, c: Cube) -> bool {
+        self.index(c).is_some()
+    }
+
+    pub fn contains(&self, c: Cube) -> bool {
+        self.index(c)
+            .is_some_and(|i| self.bits[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    /// Returns whether the cube was not set before.
+    pub fn insert(&mut self, c: Cube) -> bool {
+        let i = self.index(c).expect("cube outside of the voxel grid");
+        let was_set = self.bits[i / 64] & (1 << (i % 64)) != 0;
+        self.bits[i / 64] |= 1 << (i % 64);
+        !was_set
+    }
+}
@@ -0,0 +1,127 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Ore,
+    Clay,
+    Obsidian,
+    Geode,
+}
+
+impl Resource {
+    pub const COUNT: usize = 4;
+    pub const ALL: [Resource; Resource::COUNT] = [
+        Resource::Ore,
+        Resource::Clay,
+        Resource::Obsidian,
+        Resource::Geode,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::Ore => "ore",
+            Resource::Clay => "clay",
+            Resource::Obsidian => "obsidian",
+            Resource::Geode => "geode",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Resource::ALL
+            .into_iter()
+            .find(|resource| resource.name() == name)
+    }
+}
+
+/// One amount per resource, indexed by `Resource as usize`.
+pub type Amounts = [u32; Resource::COUNT];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blueprint {
+    pub id: u32,
+    /// What a robot collecting each resource costs.
+    pub costs: [Amounts; Resource::COUNT],
+}
+
+impl Blueprint {
+    pub fn cost(&self, robot: Resource) -> &Amounts {
+        &self.costs[robot as usize]
+    }
+
+    /// The most of each resource that can be spent in a single minute, building more robots
+    /// for it than that is pointless. Geode robots are never capped.
+    pub fn robot_caps(&self) -> Amounts {
+        let mut caps = [0; Resource::COUNT];
+        for cost in &self.costs {
+            for (cap, &amount) in caps.iter_mut().zip(cost) {
+                *cap = (*cap).max(amount);
+            }
+        }
+        caps[Resource::Geode as usize] = u32::MAX;
+        caps
+    }
+
+    /// Parses `Blueprint <id>: Each <resource> robot costs <n> <resource> [and ...].`, with
+    /// any whitespace, including line breaks, between the words.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (header, rest) = text
+            .split_once(':')
+            .ok_or_else(|| format!("missing ':' in {text:?}"))?;
+        let id = match header.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["Blueprint", id] => id.parse().map_err(|_| format!("invalid id {id:?}"))?,
+            _ => return Err(format!("invalid blueprint header {header:?}")),
+        };
+
+        let mut costs = [None; Resource::COUNT];
+        for sentence in rest.split('.').filter(|s| !s.trim().is_empty()) {
+            let words = sentence.split_whitespace().collect::<Vec<_>>();
+            let (robot, mut amounts) = match words.as_slice() {
+                ["Each", robot, "robot", "costs", amounts @ ..] => (*robot, amounts),
+                _ => return Err(format!("blueprint {id}: cannot read {:?}", sentence.trim())),
+            };
+            let robot = Resource::from_name(robot)
+                .ok_or_else(|| format!("blueprint {id}: unknown robot {robot:?}"))?;
+
+            let mut cost = [0; Resource::COUNT];
+            loop {
+                let [amount, resource, tail @ ..] = amounts else {
+                    return Err(format!("blueprint {id}: incomplete {robot:?} robot cost"));
+                };
+                let resource = Resource::from_name(resource)
+                    .ok_or_else(|| format!("blueprint {id}: unknown resource {resource:?}"))?;
+                cost[resource as usize] = amount
+                    .parse()
+                    .map_err(|_| format!("blueprint {id}: invalid amount {amount:?}"))?;
+                match tail {
+                    [] => break,
+                    ["and", tail @ ..] => amounts = tail,
+                    _ => return Err(format!("blueprint {id}: unexpected {tail:?}")),
+                }
+            }
+
+            if costs[robot as usize].replace(cost).is_some() {
+                return Err(format!("blueprint {id}: {robot:?} robot listed twice"));
+            }
+        }
+
+        let mut complete = [[0; Resource::COUNT]; Resource::COUNT];
+        for (robot, cost) in Resource::ALL.into_iter().zip(costs) {
+            complete[robot as usize] =
+                cost.ok_or_else(|| format!("blueprint {id}: no cost for {robot:?} robot"))?;
+        }
+        Ok(Self {
+            id,
+            costs: complete,
+        })
+    }
+}
+
+/// Every blueprint in `input`, however they are split across lines.
+pub fn parse_blueprints(input: &str) -> Result<Vec<Blueprint>, String> {
+    input
+        .match_indices("Blueprint")
+        .map(|(start, _)| start)
+        .chain([input.len()])
+        .collect::<Vec<_>>()
+        .windows(2)
+        .map(|bounds| Blueprint::parse(&input[bounds[0]..bounds[1]]))
+        .collect()
+}
@@ -1,220 +1,280 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-
-#[derive(Clone, PartialEq, Eq, Hash)]
-enum Material {
-    Ore,
-    Clay,
-    Obsidian,
-    Geode,
-}
+mod blueprint;
+mod plan;
+
+use blueprint::{parse_blueprints, Amounts, Blueprint, Resource};
+use plan::Build;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::{
+    env,
+    time::{Duration, Instant},
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 struct State {
-    ore_robots: u32,
-    clay_robots: u32,
-    obsidian_robots: u32,
-    geode_robots: u32,
-    ore: u32,
-    clay: u32,
-    obsidian: u32,
-    geode: u32,
+    robots: Amounts,
+    resources: Amounts,
 }
 
 impl State {
     fn new() -> Self {
+        let mut robots = [0; Resource::COUNT];
+        robots[Resource::Ore as usize] = 1;
         Self {
-            ore_robots: 1,
-            clay_robots: 0,
-            obsidian_robots: 0,
-            geode_robots: 0,
-            ore: 0,
-            clay: 0,
-            obsidian: 0,
-            geode: 0,
+            robots,
+            resources: [0; Resource::COUNT],
         }
     }
 
-    fn mine(self) -> Self {
-        Self {
-            ore: self.ore + self.ore_robots,
-            clay: self.clay + self.clay_robots,
-            obsidian: self.obsidian + self.obsidian_robots,
-            geode: self.geode + self.geode_robots,
-            ..self
-        }
+    fn robots(&self, resource: Resource) -> u32 {
+        self.robots[resource as usize]
     }
 
-    fn construct_robot(self, materials_needed: &(u32, u32, u32)) -> Option<Self> {
-        (self.ore >= materials_needed.0
-            && self.clay >= materials_needed.1
-            && self.obsidian >= materials_needed.2)
-            .then_some({
-                let mut new_state = self.mine();
-                new_state.ore -= materials_needed.0;
-                new_state.clay -= materials_needed.1;
-                new_state.obsidian -= materials_needed.2;
-                new_state
-            })
+    fn amount(&self, resource: Resource) -> u32 {
+        self.resources[resource as usize]
     }
 
-    fn construct_ore_robot(self, blueprint: &HashMap<Material, (u32, u32, u32)>) -> Option<Self> {
-        let materials_needed = blueprint.get(&Material::Ore).unwrap();
-        self.construct_robot(materials_needed).map(|s| State {
-            ore_robots: s.ore_robots + 1,
-            ..s
-        })
+    /// Minutes to wait until `cost` is affordable, `None` if that never happens.
+    fn time_to_afford(&self, cost: &Amounts) -> Option<u32> {
+        cost.iter()
+            .zip(self.resources.iter().zip(&self.robots))
+            .map(|(&needed, (&available, &robots))| {
+                if available >= needed {
+                    Some(0)
+                } else if robots == 0 {
+                    None
+                } else {
+                    Some((needed - available).div_ceil(robots))
+                }
+            })
+            .try_fold(0, |wait, w| w.map(|w| wait.max(w)))
     }
 
-    fn construct_clay_robot(self, blueprint: &HashMap<Material, (u32, u32, u32)>) -> Option<Self> {
-        let materials_needed = blueprint.get(&Material::Clay).unwrap();
-        self.construct_robot(materials_needed).map(|s| State {
-            clay_robots: s.clay_robots + 1,
-            ..s
-        })
+    fn mine(mut self, minutes: u32) -> Self {
+        for (amount, robots) in self.resources.iter_mut().zip(self.robots) {
+            *amount += robots * minutes;
+        }
+        self
     }
 
-    fn construct_obsidian_robot(
-        self,
-        blueprint: &HashMap<Material, (u32, u32, u32)>,
-    ) -> Option<Self> {
-        let materials_needed = blueprint.get(&Material::Obsidian).unwrap();
-        self.construct_robot(materials_needed).map(|s| State {
-            obsidian_robots: s.obsidian_robots + 1,
-            ..s
-        })
+    /// Pays for `robot`, which joins the others straight away.
+    fn construct(mut self, robot: Resource, blueprint: &Blueprint) -> Self {
+        for (amount, cost) in self.resources.iter_mut().zip(blueprint.cost(robot)) {
+            *amount -= cost;
+        }
+        self.robots[robot as usize] += 1;
+        self
     }
+}
 
-    fn construct_geode_robot(self, blueprint: &HashMap<Material, (u32, u32, u32)>) -> Option<Self> {
-        let materials_needed = blueprint.get(&Material::Geode).unwrap();
-        self.construct_robot(materials_needed).map(|s| State {
-            geode_robots: s.geode_robots + 1,
-            ..s
-        })
-    }
+struct Evaluation {
+    id: u32,
+    geodes: u32,
+    elapsed: Duration,
 }
 
-fn parse_input_line(line: &str) -> HashMap<Material, (u32, u32, u32)> {
-    let (_, rest) = line.split_once(':').unwrap();
-    let mut segments = rest.trim().split_terminator('.');
-
-    let ore_robot_cost: u32 = segments
-        .next()
-        .unwrap()
-        .trim()
-        .trim_start_matches("Each ore robot costs ")
-        .trim_end_matches(" ore")
-        .parse()
-        .unwrap();
-
-    let clay_robot_cost: u32 = segments
-        .next()
-        .unwrap()
-        .trim()
-        .trim_start_matches("Each clay robot costs ")
-        .trim_end_matches(" ore")
-        .parse()
-        .unwrap();
-
-    let (obsidian_robot_ore_cost, obsidian_robot_clay_cost): (u32, u32) = {
-        let (ore, clay) = segments
-            .next()
-            .unwrap()
-            .trim()
-            .trim_start_matches("Each obsidian robot costs ")
-            .trim_end_matches(" clay")
-            .split_once(" ore and ")
-            .unwrap();
-        (ore.parse().unwrap(), clay.parse().unwrap())
-    };
+fn evaluate_blueprint(blueprint: &Blueprint, minutes: u32) -> Evaluation {
+    let start = Instant::now();
+    let (geodes, _) = count_geodes(blueprint, minutes);
+    Evaluation {
+        id: blueprint.id,
+        geodes,
+        elapsed: start.elapsed(),
+    }
+}
 
-    let (geode_robot_ore_cost, geode_robot_obsidian_cost): (u32, u32) = {
-        let (ore, clay) = segments
-            .next()
-            .unwrap()
-            .trim()
-            .trim_start_matches("Each geode robot costs ")
-            .trim_end_matches(" obsidian")
-            .split_once(" ore and ")
-            .unwrap();
-        (ore.parse().unwrap(), clay.parse().unwrap())
-    };
-    HashMap::from([
-        (Material::Ore, (ore_robot_cost, 0, 0)),
-        (Material::Clay, (clay_robot_cost, 0, 0)),
-        (
-            Material::Obsidian,
-            (obsidian_robot_ore_cost, obsidian_robot_clay_cost, 0),
-        ),
-        (
-            Material::Geode,
-            (geode_robot_ore_cost, 0, geode_robot_obsidian_cost),
-        ),
-    ])
+/// Evaluates every blueprint, in parallel with the `parallel` feature. Results are in
+/// blueprint order either way.
+#[cfg(feature = "parallel")]
+fn evaluate(blueprints: &[Blueprint], minutes: u32) -> Vec<Evaluation> {
+    blueprints
+        .par_iter()
+        .map(|blueprint| evaluate_blueprint(blueprint, minutes))
+        .collect()
 }
 
-fn part_1(blueprints: &[HashMap<Material, (u32, u32, u32)>]) -> u32 {
+#[cfg(not(feature = "parallel"))]
+fn evaluate(blueprints: &[Blueprint], minutes: u32) -> Vec<Evaluation> {
     blueprints
         .iter()
-        .enumerate()
-        .map(|(idx, blueprint)| (idx as u32 + 1) * count_geodes(blueprint, 24))
+        .map(|blueprint| evaluate_blueprint(blueprint, minutes))
+        .collect()
+}
+
+/// Evaluates `blueprints` and reports each one, along with the wall-clock time of the whole
+/// evaluation, which is less than the summed search times when running in parallel.
+fn report(blueprints: &[Blueprint], minutes: u32) -> String {
+    let start = Instant::now();
+    let evaluations = evaluate(blueprints, minutes);
+    let wall_clock = start.elapsed();
+
+    let mut report = format!("{minutes} minutes\n");
+    for evaluation in &evaluations {
+        report += &format!(
+            "blueprint {:>2}: {:>2} geodes, quality {:>3}, {:>10.3?}\n",
+            evaluation.id,
+            evaluation.geodes,
+            evaluation.id * evaluation.geodes,
+            evaluation.elapsed
+        );
+    }
+    let total = evaluations
+        .iter()
+        .map(|evaluation| evaluation.elapsed)
+        .sum::<Duration>();
+    report +=
+        &format!("search time {total:.3?} summed over blueprints, {wall_clock:.3?} wall clock\n");
+    report
+}
+
+fn part_1(blueprints: &[Blueprint]) -> u32 {
+    evaluate(blueprints, 24)
+        .iter()
+        .map(|evaluation| evaluation.id * evaluation.geodes)
         .sum()
 }
 
-fn part_2(blueprints: &[HashMap<Material, (u32, u32, u32)>]) -> u32 {
-    blueprints
+fn part_2(blueprints: &[Blueprint]) -> u32 {
+    evaluate(&blueprints[..blueprints.len().min(3)], 32)
         .iter()
-        .take(3)
-        .map(|blueprint| count_geodes(blueprint, 32))
+        .map(|evaluation| evaluation.geodes)
         .product()
 }
 
-fn count_geodes(blueprint: &HashMap<Material, (u32, u32, u32)>, minutes: u32) -> u32 {
-    let state = State::new();
-    let mut states = VecDeque::new();
-    states.push_back((0, state));
-
-    let mut max = u32::MIN;
-    let mut best_geodes = HashMap::new();
-    let mut seen_states = HashSet::new();
-    while let Some((minute, state)) = states.pop_front() {
-        if minute == minutes || !seen_states.insert(state) {
-            max = std::cmp::max(max, state.geode);
-            continue;
-        }
+/// The most geodes that can be opened in `minutes`, and the robots to build to get there.
+fn count_geodes(blueprint: &Blueprint, minutes: u32) -> (u32, Vec<Build>) {
+    let mut search = Search {
+        minutes,
+        blueprint,
+        robot_caps: blueprint.robot_caps(),
+        builds: vec![],
+        max: 0,
+        best_builds: vec![],
+    };
+    search.run(State::new(), minutes);
+    (search.max, search.best_builds)
+}
 
-        let entry = best_geodes.entry(minute).or_insert(0);
-        if *entry > state.geode {
-            continue;
-        } else {
-            *entry = state.geode;
+struct Search<'a> {
+    minutes: u32,
+    blueprint: &'a Blueprint,
+    robot_caps: Amounts,
+    builds: Vec<Build>,
+    max: u32,
+    best_builds: Vec<Build>,
+}
+
+impl Search<'_> {
+    /// Depth-first search that jumps straight to the minute the next robot gets built. Robots
+    /// beyond the most of their resource that can be spent in a minute are never built, and
+    /// branches are cut once even a new geode robot every minute cannot beat `max`.
+    fn run(&mut self, state: State, minutes_left: u32) {
+        let geodes = state.amount(Resource::Geode) + state.robots(Resource::Geode) * minutes_left;
+        if geodes > self.max {
+            self.max = geodes;
+            self.best_builds = self.builds.clone();
         }
 
-        if let Some(s) = state.construct_geode_robot(blueprint) {
-            states.push_back((minute + 1, s));
-        } else {
-            if let Some(s) = state.construct_ore_robot(blueprint) {
-                states.push_back((minute + 1, s));
-            }
+        let upper_bound = geodes + minutes_left * minutes_left.saturating_sub(1) / 2;
+        if upper_bound <= self.max {
+            return;
+        }
 
-            if let Some(s) = state.construct_clay_robot(blueprint) {
-                states.push_back((minute + 1, s));
+        for robot in Resource::ALL.into_iter().rev() {
+            if state.robots(robot) >= self.robot_caps[robot as usize] {
+                continue;
             }
-
-            if let Some(s) = state.construct_obsidian_robot(blueprint) {
-                states.push_back((minute + 1, s));
+            let Some(wait) = state.time_to_afford(self.blueprint.cost(robot)) else {
+                continue;
+            };
+            if wait + 1 >= minutes_left {
+                continue;
             }
 
-            states.push_back((minute + 1, state.mine()));
+            self.builds.push(Build {
+                minute: self.minutes - minutes_left + wait + 1,
+                robot,
+            });
+            let next = state.mine(wait + 1).construct(robot, self.blueprint);
+            self.run(next, minutes_left - wait - 1);
+            self.builds.pop();
         }
     }
-
-    max
 }
 
+const EXAMPLE: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+
+const EXAMPLE_WRAPPED: &str = "Blueprint 1:
+  Each ore robot costs 4 ore.
+  Each clay robot costs 2 ore.
+  Each obsidian robot costs 3 ore and 14 clay.
+  Each geode robot costs 2 ore and 7 obsidian.
+
+Blueprint 2:
+  Each ore robot costs 2 ore.
+  Each clay robot costs 3 ore.
+  Each obsidian robot costs 3 ore and 8 clay.
+  Each geode robot costs 3 ore and 12 obsidian.
+";
+
 fn main() {
+    let example = parse_blueprints(EXAMPLE).unwrap();
+    assert_eq!(parse_blueprints(EXAMPLE_WRAPPED).unwrap(), example);
+    assert_eq!(example[0].cost(Resource::Geode), &[2, 0, 7, 0]);
+    assert_eq!(example[1].robot_caps(), [3, 8, 12, u32::MAX]);
+    assert!(Blueprint::parse("Blueprint 3: Each ore robot costs 4 ore.").is_err());
+    assert!(Blueprint::parse("Blueprint 3: Each ore robot costs 4 gold.").is_err());
+
+    for (blueprint, minutes, expected) in [
+        (&example[0], 24, 9),
+        (&example[1], 24, 12),
+        (&example[0], 32, 56),
+        (&example[1], 32, 62),
+    ] {
+        let (geodes, builds) = count_geodes(blueprint, minutes);
+        assert_eq!(geodes, expected);
+        let narrative = plan::narrate(blueprint, &builds, minutes);
+        assert!(narrative.ends_with(&format!("you now have {expected} open geodes.\n")));
+    }
+    let (_, builds) = count_geodes(&example[0], 24);
+    assert_eq!(
+        builds
+            .iter()
+            .map(|build| (build.minute, build.robot))
+            .collect::<Vec<_>>(),
+        [
+            (3, Resource::Clay),
+            (5, Resource::Clay),
+            (7, Resource::Clay),
+            (11, Resource::Obsidian),
+            (12, Resource::Clay),
+            (15, Resource::Obsidian),
+            (18, Resource::Geode),
+            (21, Resource::Geode),
+        ]
+    );
+
     let input = std::fs::read_to_string("input").unwrap();
-    let blueprints = input.lines().map(parse_input_line).collect::<Vec<_>>();
+    let blueprints = parse_blueprints(&input).unwrap();
 
     assert_eq!(part_1(&blueprints), 1023);
     assert_eq!(part_2(&blueprints), 13520);
+
+    match env::args().nth(1).as_deref() {
+        Some("report") => {
+            print!("{}", report(&blueprints, 24));
+            let first_three = &blueprints[..blueprints.len().min(3)];
+            print!("{}", report(first_three, 32));
+        }
+        Some("plan") => {
+            let id: u32 = env::args().nth(2).unwrap().parse().unwrap();
+            let minutes = env::args().nth(3).map_or(24, |m| m.parse().unwrap());
+            let blueprint = blueprints.iter().find(|b| b.id == id).unwrap();
+            let (geodes, builds) = count_geodes(blueprint, minutes);
+            print!("{}", plan::narrate(blueprint, &builds, minutes));
+            println!("\nblueprint {id} opens {geodes} geodes in {minutes} minutes");
+        }
+        _ => (),
+    }
 }
@@ -0,0 +1,98 @@
+use crate::{
+    blueprint::{Blueprint, Resource},
+    State,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Build {
+    /// The minute the robot is started in; it is ready at the end of that minute.
+    pub minute: u32,
+    pub robot: Resource,
+}
+
+fn robot_name(robot: Resource) -> &'static str {
+    match robot {
+        Resource::Ore => "ore-collecting",
+        Resource::Clay => "clay-collecting",
+        Resource::Obsidian => "obsidian-collecting",
+        Resource::Geode => "geode-cracking",
+    }
+}
+
+fn plural(count: u32, singular: &str) -> String {
+    if count == 1 {
+        format!("{count} {singular}")
+    } else {
+        format!("{count} {singular}s")
+    }
+}
+
+/// Replays `builds` minute by minute, worded like the puzzle's walkthrough.
+pub fn narrate(blueprint: &Blueprint, builds: &[Build], minutes: u32) -> String {
+    let mut state = State::new();
+    let mut narrative = vec![];
+
+    for minute in 1..=minutes {
+        let mut lines = vec![format!("== Minute {minute} ==")];
+
+        let build = builds.iter().find(|build| build.minute == minute);
+        let mut next = state.mine(1);
+        if let Some(build) = build {
+            let spent = Resource::ALL
+                .into_iter()
+                .zip(blueprint.cost(build.robot))
+                .filter(|(_, &amount)| amount > 0)
+                .map(|(resource, amount)| format!("{amount} {}", resource.name()))
+                .collect::<Vec<_>>()
+                .join(" and ");
+            let article = match build.robot {
+                Resource::Ore | Resource::Obsidian => "an",
+                Resource::Clay | Resource::Geode => "a",
+            };
+            lines.push(format!(
+                "Spend {spent} to start building {article} {} robot.",
+                robot_name(build.robot)
+            ));
+            next = next.construct(build.robot, blueprint);
+        }
+
+        for resource in Resource::ALL {
+            let robots = state.robots(resource);
+            if robots == 0 {
+                continue;
+            }
+            let robot_name = format!("{} robot", robot_name(resource));
+            lines.push(if resource == Resource::Geode {
+                format!(
+                    "{} {} {}; you now have {}.",
+                    plural(robots, &robot_name),
+                    if robots == 1 { "cracks" } else { "crack" },
+                    plural(robots, "geode"),
+                    plural(next.amount(resource), "open geode")
+                )
+            } else {
+                format!(
+                    "{} {} {robots} {}; you now have {} {}.",
+                    plural(robots, &robot_name),
+                    if robots == 1 { "collects" } else { "collect" },
+                    resource.name(),
+                    next.amount(resource),
+                    resource.name()
+                )
+            });
+        }
+
+        if let Some(build) = build {
+            lines.push(format!(
+                "The new {} robot is ready; you now have {} of them.",
+                robot_name(build.robot),
+                next.robots(build.robot)
+            ));
+        }
+
+        narrative.push(lines.join("\n"));
+        state = next;
+    }
+
+    narrative.join("\n\n") + "\n"
+}
@@ -0,0 +1,203 @@
+use grid::Grid;
+
+/// Rows added at a time when elves reach the top or bottom edge.
+const ROW_CHUNK: usize = 16;
+
+/// Elves as one bit per cell, each row a run of `u64` words so a whole word of cells is
+/// checked at once. Bit `x % 64` of word `x / 64` is column `x`. The board keeps an empty
+/// border around the elves and grows whenever they reach it.
+pub struct Bitboard {
+    words: usize,
+    height: usize,
+    cells: Vec<u64>,
+}
+
+/// Every cell's western neighbour, moved onto the cell.
+fn from_west(row: &[u64], i: usize) -> u64 {
+    (row[i] << 1) | if i > 0 { row[i - 1] >> 63 } else { 0 }
+}
+
+/// Every cell's eastern neighbour, moved onto the cell.
+fn from_east(row: &[u64], i: usize) -> u64 {
+    (row[i] >> 1)
+        | if i + 1 < row.len() {
+            row[i + 1] << 63
+        } else {
+            0
+        }
+}
+
+/// Like `from_west`, two cells away.
+fn from_west_2(row: &[u64], i: usize) -> u64 {
+    (row[i] << 2) | if i > 0 { row[i - 1] >> 62 } else { 0 }
+}
+
+fn from_east_2(row: &[u64], i: usize) -> u64 {
+    (row[i] >> 2)
+        | if i + 1 < row.len() {
+            row[i + 1] << 62
+        } else {
+            0
+        }
+}
+
+impl Bitboard {
+    pub fn from_grid(grid: &Grid<bool>) -> Self {
+        // At least one word per row, so that an empty grid still has a row to grow from.
+        let words = grid.width().div_ceil(64).max(1);
+        let mut board = Self {
+            words,
+            height: grid.height(),
+            cells: vec![0; words * grid.height()],
+        };
+        for position in grid.find_all(|&elf| elf) {
+            board.cells[position.y * board.words + position.x / 64] |= 1 << (position.x % 64);
+        }
+        board.grow();
+        board
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.cells[y * self.words..(y + 1) * self.words]
+    }
+
+    /// Makes sure there is an empty row above and below and an empty column on either side.
+    fn grow(&mut self) {
+        let words = self.words;
+        if self.cells.iter().step_by(words).any(|word| word & 1 != 0) {
+            self.cells = self
+                .cells
+                .chunks(words)
+                .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+                .collect();
+            self.words += 1;
+        }
+
+        let words = self.words;
+        if self.cells[words - 1..]
+            .iter()
+            .step_by(words)
+            .any(|word| word >> 63 != 0)
+        {
+            self.cells = self
+                .cells
+                .chunks(words)
+                .flat_map(|row| row.iter().copied().chain(std::iter::once(0)))
+                .collect();
+            self.words += 1;
+        }
+
+        if self.height == 0 || self.row(0).iter().any(|&word| word != 0) {
+            self.cells
+                .splice(0..0, std::iter::repeat_n(0, ROW_CHUNK * self.words));
+            self.height += ROW_CHUNK;
+        }
+        if self.row(self.height - 1).iter().any(|&word| word != 0) {
+            self.cells
+                .extend(std::iter::repeat_n(0, ROW_CHUNK * self.words));
+            self.height += ROW_CHUNK;
+        }
+    }
+
+    /// Plays one round with the direction order rotated by `turn`, returning whether any elf
+    /// moved.
+    pub fn round(&mut self, turn: usize) -> bool {
+        let (words, height) = (self.words, self.height);
+
+        // North, south, west and east proposals, in the puzzle's order.
+        let mut proposals = [
+            vec![0; self.cells.len()],
+            vec![0; self.cells.len()],
+            vec![0; self.cells.len()],
+            vec![0; self.cells.len()],
+        ];
+        for y in 1..height - 1 {
+            let (north, row, south) = (self.row(y - 1), self.row(y), self.row(y + 1));
+            for i in 0..words {
+                let around = |r: &[u64]| from_west(r, i) | r[i] | from_east(r, i);
+                let north_free = !around(north);
+                let south_free = !around(south);
+                let west_free = !(from_west(north, i) | from_west(row, i) | from_west(south, i));
+                let east_free = !(from_east(north, i) | from_east(row, i) | from_east(south, i));
+                let free = [north_free, south_free, west_free, east_free];
+
+                let mut remaining = row[i] & !(north_free & south_free & west_free & east_free);
+                for direction in 0..4 {
+                    let direction = (turn + direction) % 4;
+                    let proposing = remaining & free[direction];
+                    proposals[direction][y * words + i] = proposing;
+                    remaining &= !proposing;
+                }
+            }
+        }
+
+        // Two elves can only propose the same cell from opposite sides, so a proposal wins
+        // unless the elf two cells further on proposes the opposite direction. Elves may move
+        // into the border rows, which never propose anything and stand in for rows past the
+        // edge.
+        let [north, south, west, east] = &proposals;
+        let mut moved = false;
+        let mut next = vec![0; self.cells.len()];
+        let row = |y: usize| y * words..(y + 1) * words;
+        for y in 0..height {
+            let two_above = &south[row(y.saturating_sub(2))];
+            let two_below = &north[row((y + 2).min(height - 1))];
+            let (north_row, south_row) = (&north[row(y)], &south[row(y)]);
+            let (west_row, east_row) = (&west[row(y)], &east[row(y)]);
+            let (from_below, from_above) = (
+                &north[row((y + 1).min(height - 1))],
+                &south[row(y.saturating_sub(1))],
+            );
+            for i in 0..words {
+                let leaving = (north_row[i] & !two_above[i])
+                    | (south_row[i] & !two_below[i])
+                    | (west_row[i] & !from_west_2(east_row, i))
+                    | (east_row[i] & !from_east_2(west_row, i));
+                let arriving = (from_below[i] ^ from_above[i])
+                    | (from_east(west_row, i) ^ from_west(east_row, i));
+                moved |= leaving != 0;
+                next[y * words + i] = (self.cells[y * words + i] & !leaving) | arriving;
+            }
+        }
+
+        self.cells = next;
+        self.grow();
+        moved
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Empty cells in the smallest rectangle containing every elf.
+    pub fn count_empty(&self) -> usize {
+        let occupied_rows = (0..self.height)
+            .filter(|&y| self.row(y).iter().any(|&word| word != 0))
+            .collect::<Vec<_>>();
+        let columns =
+            self.cells
+                .chunks(self.words)
+                .fold(vec![0; self.words], |mut columns, row| {
+                    columns.iter_mut().zip(row).for_each(|(c, word)| *c |= word);
+                    columns
+                });
+        let min_x = columns
+            .iter()
+            .position(|&word| word != 0)
+            .map(|i| i * 64 + columns[i].trailing_zeros() as usize);
+        let max_x = columns
+            .iter()
+            .rposition(|&word| word != 0)
+            .map(|i| i * 64 + 63 - columns[i].leading_zeros() as usize);
+
+        match (occupied_rows.first(), occupied_rows.last(), min_x, max_x) {
+            (Some(min_y), Some(max_y), Some(min_x), Some(max_x)) => {
+                (max_y - min_y + 1) * (max_x - min_x + 1) - self.len()
+            }
+            _ => 0,
+        }
+    }
+}
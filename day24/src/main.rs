@@ -1,20 +1,9 @@
+use grid::{Grid, Position};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 const WIDTH: usize = 120;
 const HEIGHT: usize = 25;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Coordinates {
-    x: usize,
-    y: usize,
-}
-
-impl Coordinates {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-}
-
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
     Up,
@@ -25,14 +14,14 @@ enum Direction {
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 struct Blizzard {
-    coordinates: Coordinates,
+    coordinates: Position,
     direction: Direction,
 }
 
 impl Blizzard {
     fn new(x: usize, y: usize, direction: Direction) -> Self {
         Self {
-            coordinates: Coordinates { x, y },
+            coordinates: Position { x, y },
             direction,
         }
     }
@@ -74,7 +63,7 @@ impl Blizzard {
 #[derive(Clone, PartialEq, Eq, Hash)]
 struct State {
     minute: usize,
-    expedition: Option<Coordinates>,
+    expedition: Option<Position>,
 }
 
 impl State {
@@ -89,32 +78,24 @@ impl State {
         Self {
             expedition: None,
             minute: other.minute,
-            ..other
         }
     }
 
     fn get_expedition_possible_positions(
         &self,
-        valley_entrance: Coordinates,
+        valley_entrance: Position,
         blizzards: &HashSet<Blizzard>,
-    ) -> Vec<Option<Coordinates>> {
+    ) -> Vec<Option<Position>> {
         if let Some(exp) = self.expedition {
             let mut positions = vec![];
             if self.can_move(exp, blizzards) {
                 positions.push(Some(exp)); // don't move
             }
-            if exp.y != 0 && self.can_move(Coordinates::new(exp.x, exp.y - 1), blizzards) {
-                positions.push(Some(Coordinates::new(exp.x, exp.y - 1))); // go up
-            }
-            if exp.y != HEIGHT - 1 && self.can_move(Coordinates::new(exp.x, exp.y + 1), blizzards) {
-                positions.push(Some(Coordinates::new(exp.x, exp.y + 1))); // go down
-            }
-            if exp.x != 0 && self.can_move(Coordinates::new(exp.x - 1, exp.y), blizzards) {
-                positions.push(Some(Coordinates::new(exp.x - 1, exp.y))); // go left
-            }
-            if exp.x != WIDTH - 1 && self.can_move(Coordinates::new(exp.x + 1, exp.y), blizzards) {
-                positions.push(Some(Coordinates::new(exp.x + 1, exp.y))); // go right
-            }
+            positions.extend(
+                grid::neighbours4(exp, WIDTH, HEIGHT)
+                    .filter(|&p| self.can_move(p, blizzards))
+                    .map(Some),
+            );
             positions
         } else if self.can_move(valley_entrance, blizzards) {
             vec![None, Some(valley_entrance)] // wait before entering valley, enter valley
@@ -123,7 +104,7 @@ impl State {
         }
     }
 
-    fn can_move(&self, coordinates: Coordinates, blizzards: &HashSet<Blizzard>) -> bool {
+    fn can_move(&self, coordinates: Position, blizzards: &HashSet<Blizzard>) -> bool {
         [
             Direction::Up,
             Direction::Down,
@@ -141,8 +122,8 @@ impl State {
 
 fn measure_quickest_path(
     mut initial_state: State,
-    valley_entrance: Coordinates,
-    valley_exit: Coordinates,
+    valley_entrance: Position,
+    valley_exit: Position,
     blizzard_history: &mut HashMap<usize, HashSet<Blizzard>>,
 ) -> State {
     initial_state.minute += 1;
@@ -150,7 +131,7 @@ fn measure_quickest_path(
     states.push_back(initial_state);
     let mut seen_states: HashSet<State> = HashSet::new();
     while let Some(mut state) = states.pop_front() {
-        if seen_states.get(&state).is_some() {
+        if seen_states.contains(&state) {
             continue;
         }
         seen_states.insert(state.clone());
@@ -186,28 +167,27 @@ fn measure_quickest_path(
 
 fn main() {
     let input = std::fs::read_to_string("input").unwrap();
-    let input = input.lines().collect::<Vec<_>>();
+    let valley = Grid::parse(&input, |c| c);
+    assert_eq!((valley.width(), valley.height()), (WIDTH + 2, HEIGHT + 2));
 
     let mut blizzards = HashSet::new();
-    for (y, line) in input.iter().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let (x, y) = (x - 1, y - 1);
-            match c {
-                '^' => blizzards.insert(Blizzard::new(x, y, Direction::Up)),
-                'v' => blizzards.insert(Blizzard::new(x, y, Direction::Down)),
-                '<' => blizzards.insert(Blizzard::new(x, y, Direction::Left)),
-                '>' => blizzards.insert(Blizzard::new(x, y, Direction::Right)),
-                _ => false,
-            };
-        }
+    for (position, c) in valley.iter() {
+        let (x, y) = (position.x.wrapping_sub(1), position.y.wrapping_sub(1));
+        match c {
+            '^' => blizzards.insert(Blizzard::new(x, y, Direction::Up)),
+            'v' => blizzards.insert(Blizzard::new(x, y, Direction::Down)),
+            '<' => blizzards.insert(Blizzard::new(x, y, Direction::Left)),
+            '>' => blizzards.insert(Blizzard::new(x, y, Direction::Right)),
+            _ => false,
+        };
     }
 
     let mut blizzard_history = HashMap::new();
     blizzard_history.insert(0, blizzards);
     let state = measure_quickest_path(
         State::new(),
-        Coordinates::new(0, 0),
-        Coordinates::new(WIDTH - 1, HEIGHT - 1),
+        Position::new(0, 0),
+        Position::new(WIDTH - 1, HEIGHT - 1),
         &mut blizzard_history,
     );
 
@@ -216,15 +196,15 @@ fn main() {
 
     let state = measure_quickest_path(
         State::from_state(state),
-        Coordinates::new(WIDTH - 1, HEIGHT - 1),
-        Coordinates::new(0, 0),
+        Position::new(WIDTH - 1, HEIGHT - 1),
+        Position::new(0, 0),
         &mut blizzard_history,
     );
 
     let state = measure_quickest_path(
-        State::from(state),
-        Coordinates::new(0, 0),
-        Coordinates::new(WIDTH - 1, HEIGHT - 1),
+        State::from_state(state),
+        Position::new(0, 0),
+        Position::new(WIDTH - 1, HEIGHT - 1),
         &mut blizzard_history,
     );
 
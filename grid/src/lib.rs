@@ -0,0 +1,284 @@
+use std::ops::{Index, IndexMut};
+
+/// Up, right, down, left.
+pub const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// All eight surrounding cells, row by row.
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Position {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Moves by `offset`, returning `None` when leaving a `width` x `height` area.
+    pub fn offset(self, offset: (isize, isize), width: usize, height: usize) -> Option<Self> {
+        let x = self.x.checked_add_signed(offset.0)?;
+        let y = self.y.checked_add_signed(offset.1)?;
+        (x < width && y < height).then_some(Self { x, y })
+    }
+}
+
+pub fn neighbours4(
+    position: Position,
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = Position> {
+    OFFSETS_4
+        .into_iter()
+        .filter_map(move |offset| position.offset(offset, width, height))
+}
+
+pub fn neighbours8(
+    position: Position,
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = Position> {
+    OFFSETS_8
+        .into_iter()
+        .filter_map(move |offset| position.offset(offset, width, height))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from row-major `cells`.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "cell count does not match size"
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses one row per line, converting every character with `f`.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = vec![];
+        for line in input.lines() {
+            let row_start = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let row_width = cells.len() - row_start;
+            if height == 0 {
+                width = row_width;
+            }
+            assert_eq!(row_width, width, "row {height} has a different width");
+            height += 1;
+        }
+
+        Self::from_vec(width, height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        (position.x < self.width && position.y < self.height)
+            .then(|| &self.cells[position.y * self.width + position.x])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        (position.x < self.width && position.y < self.height)
+            .then(|| &mut self.cells[position.y * self.width + position.x])
+    }
+
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> {
+        neighbours4(position, self.width, self.height)
+    }
+
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> {
+        neighbours8(position, self.width, self.height)
+    }
+
+    /// Positions from `start` (exclusive) stepping by `direction` until the edge.
+    pub fn ray(
+        &self,
+        start: Position,
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = Position> {
+        let (width, height) = (self.width, self.height);
+        std::iter::successors(start.offset(direction, width, height), move |position| {
+            position.offset(direction, width, height)
+        })
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.cells[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        (0..self.height).map(move |y| &self[Position::new(x, y)])
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Position::new(i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn find_all<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Position> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self::from_vec(width, height, vec![value; width * height])
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position).expect("position out of bounds")
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        self.get_mut(position).expect("position out of bounds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456", |c| c.to_digit(10).unwrap())
+    }
+
+    #[test]
+    fn parse_reads_rows() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Position::new(0, 0)], 1);
+        assert_eq!(grid[Position::new(2, 1)], 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "row 1 has a different width")]
+    fn parse_rejects_ragged_rows() {
+        Grid::parse("123\n45", |c| c);
+    }
+
+    #[test]
+    fn get_out_of_bounds() {
+        let grid = digits();
+        assert_eq!(grid.get(Position::new(2, 1)), Some(&6));
+        assert_eq!(grid.get(Position::new(3, 0)), None);
+        assert_eq!(grid.get(Position::new(0, 2)), None);
+        let mut grid = grid;
+        assert!(grid.get_mut(Position::new(3, 1)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "position out of bounds")]
+    fn index_out_of_bounds() {
+        let _ = digits()[Position::new(3, 0)];
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits();
+        assert_eq!(grid.row(1).copied().collect::<Vec<_>>(), [4, 5, 6]);
+        assert_eq!(grid.column(0).copied().collect::<Vec<_>>(), [1, 4]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 out of bounds")]
+    fn column_out_of_bounds() {
+        let _ = digits().column(3);
+    }
+
+    #[test]
+    fn ray_stops_at_every_edge() {
+        let grid = Grid::filled(4, 3, 0);
+        let ray = |x, y, direction| {
+            grid.ray(Position::new(x, y), direction)
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ray(1, 1, (0, -1)), [(1, 0)]);
+        assert_eq!(ray(1, 1, (1, 0)), [(2, 1), (3, 1)]);
+        assert_eq!(ray(1, 1, (0, 1)), [(1, 2)]);
+        assert_eq!(ray(1, 1, (-1, 0)), [(0, 1)]);
+        assert_eq!(ray(0, 0, (1, 1)), [(1, 1), (2, 2)]);
+        assert_eq!(ray(3, 2, (1, 0)), []);
+        assert_eq!(ray(0, 0, (-1, -1)), []);
+    }
+
+    #[test]
+    fn neighbours_at_corners() {
+        let grid = Grid::filled(3, 3, 0);
+        let sorted = |positions: Vec<Position>| {
+            let mut positions = positions
+                .into_iter()
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>();
+            positions.sort();
+            positions
+        };
+        let corner = Position::new(0, 0);
+        assert_eq!(sorted(grid.neighbours4(corner).collect()), [(0, 1), (1, 0)]);
+        assert_eq!(
+            sorted(grid.neighbours8(corner).collect()),
+            [(0, 1), (1, 0), (1, 1)]
+        );
+        let corner = Position::new(2, 2);
+        assert_eq!(sorted(grid.neighbours4(corner).collect()), [(1, 2), (2, 1)]);
+        assert_eq!(
+            sorted(grid.neighbours8(corner).collect()),
+            [(1, 1), (1, 2), (2, 1)]
+        );
+        assert_eq!(grid.neighbours4(Position::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Position::new(1, 1)).count(), 8);
+    }
+}